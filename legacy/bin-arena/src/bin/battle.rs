@@ -9,8 +9,26 @@ extern crate anna_model;
 extern crate anna_simulation;
 extern crate anna_utils;
 
+/**
+ * battle
+ *
+ * Plays a network against `SNetLex` until the SPRT decides. The decisions of the network are
+ * recorded in the directory given as second argument, `battle-<thread>.jsonl` by thread.
+ *
+ * cd bin-arena
+ * env RUST_LOG="info" cargo run --release --bin battle ../resources/ [/tmp/battle]
+ *
+ **/
 fn main() -> () {
-  use std::{collections::HashSet, env::args, iter::FromIterator, ops::Neg, path::Path};
+  use std::{
+    collections::HashSet,
+    env::args,
+    fs::File,
+    io::{self, BufWriter, Write},
+    iter::FromIterator,
+    ops::Neg,
+    path::Path,
+  };
 
   use nnet::Network;
   use rayon::{current_num_threads, prelude::*};
@@ -27,15 +45,16 @@ fn main() -> () {
   use anna_learning::plan::*;
 
   use anna_simulation::{
-    perf::run_benchmark,
+    perf::{duplicate_rates, Decision, RateStats, Sprt},
     players::*,
     players_kuhn::Kuhn2,
     players_lex::Lex,
     players_mul::{PlayersMul2, PlayersMulX},
+    players_recorder::PlayersRecorder,
     Sim,
   };
 
-  use anna_utils::{bincode, logging, math, random};
+  use anna_utils::{bincode, logging, random};
 
   logging::init();
 
@@ -51,6 +70,8 @@ fn main() -> () {
   let ref path_networks = path_data.join("networks");
   let ref path_synthetic = path_data.join("synthetic");
 
+  let path_records_str = args().nth(2);
+
   // let plan = plan_kuhn2();
   let plan_b = plan_texas_limit_n(2, &path_synthetic);
   let plan = plan_texas_limit_zero_n(2, &path_synthetic);
@@ -140,6 +161,15 @@ fn main() -> () {
 
     players0.reset(&network_a, Policy::P).unwrap();
 
+    let records: Box<Write + Send> = match path_records_str {
+      Some(ref path_records) => {
+        let path = Path::new(path_records).join(format!("battle-{}.jsonl", thread_id));
+        Box::new(BufWriter::new(File::create(path).unwrap()))
+      }
+      None => Box::new(io::sink()),
+    };
+    let players0 = PlayersRecorder::new(players0, records);
+
     let mut xs = Vec::new();
     for player_id in 1..plan.profile.players {
       let players_lex = SNetLex::new(
@@ -161,42 +191,41 @@ fn main() -> () {
 
   let batch_size = 128;
 
-  let mut totals: Vec<f32> = vec![0.0; 2];
+  // Stop once the network is decided 10 mbb/hand better or worse.
+  let sprt = Sprt::new(10.);
+
+  let mut rates: Vec<f32> = Vec::new();
   let mut session_id = 1;
 
   loop {
-    let results: Vec<(usize, f32)> = benchmark_players
+    let results: Vec<Vec<f32>> = benchmark_players
       .par_iter_mut()
-      .enumerate()
-      .map(|(thread_id, players)| {
-        let funds = (0..plan.profile.players).map(|seat_id| (seat_id, fund_init)).collect();
-        let rates = run_benchmark(sim, &plan.eval, &funds, batch_size / threads, players);
-
-        // let network_id = if thread_id % 2 == 0 { 0 } else { 1 };
-        let network_id = 0;
-
-        (network_id, rates[plan.profile.players - 1].neg())
+      .map(|players| {
+        let ref mut rng = rand::thread_rng();
+        let funds: Vec<_> = (0..plan.profile.players).map(|seat_id| (seat_id, fund_init)).collect();
+        let deals = (batch_size / threads / funds.len()).max(1);
+
+        duplicate_rates(rng, sim, &plan.eval, &funds, deals, players)
+          .iter()
+          .map(|deal_rates| deal_rates[plan.profile.players - 1].neg())
+          .collect()
       })
       .collect();
 
-    // let mut network_rates = vec![Vec::new(); 2];
-    let mut network_rates = vec![Vec::new(); 1];
-
-    for (i, rate) in results {
-      network_rates[i].push(rate);
+    let session = RateStats::new(&results.concat());
+    for xs in results {
+      rates.extend(xs);
     }
 
-    let mut bench_summary = String::new();
-
-    for (i, rates) in network_rates.iter().enumerate() {
-      let session = math::mean(rates);
-      totals[i] += session;
-      let total = totals[i] / session_id as f32;
-      bench_summary.push_str(format!(" {:08.0} ({:08.0})", total, session).as_str());
-    }
+    let stats = RateStats::new(&rates);
+    let decision = sprt.decide(&stats);
 
     // Logging
-    println!(" {:12} | {}", session_id * batch_size, bench_summary);
+    println!(" {:12} | {} ({:08.0}) {:?}", session_id * batch_size, stats, session.mean, decision);
+
+    if decision != Decision::Undecided {
+      break;
+    }
 
     session_id = session_id + 1;
   }
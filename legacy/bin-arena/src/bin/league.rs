@@ -0,0 +1,184 @@
+#[macro_use]
+extern crate log;
+extern crate nnet;
+extern crate rand;
+
+extern crate anna_learning;
+extern crate anna_model;
+extern crate anna_simulation;
+extern crate anna_utils;
+
+/**
+ * league
+ *
+ * Rates every network archived in a training directory against the rule-based bots (`Lex`,
+ * `SNetLex`, the `PlayersRules` presets), the ratings being kept in `league.json` so that later
+ * runs slot new checkpoints in.
+ *
+ * cd bin-arena
+ * env RUST_LOG="info" cargo run --release --bin league ../resources/ ../resources/training/tl-2 [matches]
+ *
+ **/
+fn main() -> () {
+  use std::{collections::HashMap, env::args, fs, path::Path};
+
+  use nnet::Network;
+
+  use anna_model::Money;
+
+  use anna_learning::{
+    agent_episode::{Episode, Policy},
+    qnet::QNet,
+    snet::SNet,
+    snet_lex::SNetLex,
+  };
+
+  use anna_learning::plan::*;
+
+  use anna_simulation::{
+    league::{league_run, schedule, Ratings, Schedule},
+    players::*,
+    players_lex::Lex,
+    players_rules::{PlayersRules, Style, STYLES},
+  };
+
+  use anna_utils::{bincode, logging, random};
+
+  logging::init();
+
+  let fund_init = Money::new(100, 0);
+
+  let path_data_str = args().nth(1).unwrap();
+  let ref path_data = Path::new(path_data_str.as_str());
+  let ref path_graphs = path_data.join("graphs");
+  let ref path_networks = path_data.join("networks");
+  let ref path_synthetic = path_data.join("synthetic");
+
+  let path_training_str = args().nth(2).unwrap();
+  let ref path_training = Path::new(path_training_str.as_str());
+  let ref path_ratings = path_training.join("league.json");
+
+  // Round robin unless a count of sampled matches is given.
+  let schedule_kind = match args().nth(3) {
+    Some(matches) => Schedule::Sampled(matches.parse::<usize>().unwrap()),
+    None => Schedule::RoundRobin,
+  };
+
+  let plan_b = plan_texas_limit_n(2, &path_synthetic);
+  let plan = plan_texas_limit_zero_n(2, &path_synthetic);
+
+  // Networks by name, `epoch-<epoch>/network-<i>`.
+  let mut networks: HashMap<String, Network> = HashMap::new();
+  for entry in fs::read_dir(path_training).unwrap() {
+    let path_epoch = entry.unwrap().path();
+    if !path_epoch.is_dir() {
+      continue;
+    }
+
+    for entry in fs::read_dir(&path_epoch).unwrap() {
+      let path = entry.unwrap().path();
+      let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+      if file_name.starts_with("network-") && file_name.ends_with("-p.data") {
+        let name = format!(
+          "{}/{}",
+          path_epoch.file_name().unwrap().to_string_lossy(),
+          file_name.trim_right_matches("-p.data")
+        );
+        networks.insert(name, bincode::deserialize_from_file(&path).unwrap());
+      }
+    }
+  }
+
+  let ref mut thread_rng = rand::thread_rng();
+
+  let ref sim = plan.sim();
+
+  let snet = SNet::new(path_graphs, &plan.profile, &plan.eval, &plan.snet_hiddens).unwrap();
+
+  let cards_encoder_create = |rng| match plan.cards_encoders(rng, &snet, &plan.eval, path_networks)
+  {
+    CardsEncoders::Binary(encoder) => encoder,
+    _ => unimplemented!(),
+  };
+
+  let cards_encoder_strength_create =
+    |rng| match plan_b.cards_encoders(rng, &snet, &plan_b.eval, path_networks) {
+      CardsEncoders::StrengthNNet(encoder) => encoder,
+      _ => unimplemented!(),
+    };
+
+  let actions_encoder = plan.actions_encoder();
+
+  let cards_encoder = cards_encoder_create(random::rseed(thread_rng));
+
+  let qnet =
+    QNet::new(path_graphs, sim, actions_encoder.as_ref(), &cards_encoder, &plan.qnet_hiddens)
+      .unwrap();
+
+  let mut ratings = Ratings::load_or_new(path_ratings, 32.).unwrap();
+
+  let mut names: Vec<String> =
+    vec!["lex", "snet_lex", "rand"].iter().map(|s| s.to_string()).collect();
+  names.extend(STYLES.iter().map(|style| format!("rules/{}", style)));
+  names.extend(networks.keys().cloned());
+  names.sort();
+
+  for name in names.iter() {
+    ratings.insert(name);
+  }
+
+  let mut rng = random::rseed(thread_rng);
+
+  let players = |name: &str| -> Box<Players<()>> {
+    let mut rng = random::rseed(&mut rand::thread_rng());
+
+    match name {
+      "lex" => Box::new(Lex::new(rng, 64, &plan.action_class, &plan.eval, &plan.profile)),
+      "snet_lex" => Box::new(SNetLex::new(
+        random::rseed(&mut rng),
+        cards_encoder_strength_create(random::rseed(&mut rng)),
+        &plan.action_class,
+        &plan.profile,
+      )),
+      "rand" => Box::new(PlayersRand::new(rng, &plan.action_class, sim.blind_biggest)),
+      name if name.starts_with("rules/") => Box::new(PlayersRules::new(
+        rng,
+        64,
+        &plan.action_class,
+        &plan.eval,
+        &plan.profile,
+        Style::preset(name.trim_left_matches("rules/")).unwrap(),
+      )),
+      name => {
+        let mut episode = Episode::new(
+          &mut rng,
+          actions_encoder.as_ref(),
+          cards_encoder_create(random::rseed(&mut rand::thread_rng())),
+          qnet.p_predict().unwrap(),
+          qnet.q_predict().unwrap(),
+          &qnet,
+        )
+        .unwrap();
+
+        episode.reset(&networks[name], Policy::P).unwrap();
+        Box::new(episode)
+      }
+    }
+  };
+
+  let matches = schedule(&mut rng, &names, schedule_kind);
+  info!("League of {} entries, {} matches", names.len(), matches.len());
+
+  for (a, b) in matches {
+    league_run(&mut rng, sim, &plan.eval, fund_init, &mut ratings, &[(a, b)], 4096, &players);
+    ratings.save(path_ratings).unwrap();
+  }
+
+  for (name, rating) in ratings.table() {
+    println!(
+      " {:40} | {:7.1} | {:6} matches | {:10} hands",
+      name, rating.elo, rating.matches, rating.hands
+    );
+  }
+}
@@ -5,7 +5,6 @@ extern crate ctrlc;
 extern crate nnet;
 extern crate rand;
 extern crate rayon;
-extern crate redis;
 
 extern crate anna_arena;
 extern crate anna_eval;
@@ -25,7 +24,6 @@ use std::{
 };
 
 use rand::StdRng;
-use redis::Client;
 
 use anna_eval::Eval;
 use anna_learning::{
@@ -168,7 +166,7 @@ where
   };
 
   let networks_send =
-    |worker: &Sender<Event>, epoch_start, system: &mut AgentSystem, benchmark, save| {
+    |worker: &Sender<Event>, epoch_start, system: &mut AgentSystem<_, _>, benchmark, save| {
       worker
         .send(Event::Network {
           epoch: epoch_start,
@@ -192,10 +190,8 @@ where
   let ref qnet =
     QNet::new(path_graphs, sim, actions_encoder.as_ref(), &cards_encoder, &plan.qnet_hiddens)?;
 
-  let redis = redis::Client::open("redis://127.0.0.1/")?;
-
   let mut system = AgentSystem::new(
-    &redis,
+    |i| plan.replay_stores(path_training, i),
     qnet,
     plan.agents,
     plan.agents_hist,
@@ -203,6 +199,7 @@ where
     plan.p_reservoir_min,
     plan.p_reservoir_size,
     plan.q_buffer_size,
+    &plan.agent_params.q_replay,
   )?;
   let ref agent_params = plan.agent_params;
 
@@ -248,8 +245,9 @@ where
       system.agents[i].p_network.context.set(&network_p).unwrap();
       system.agents[i].q_network.context.set(&network_q).unwrap();
 
-      system.agents[i].p_behaviors.snapshot_load();
-      system.agents[i].q_transitions.snapshot_load();
+      if !system.agents[i].snapshot_load() {
+        warn!("Replay memories of agent {} not found, starting empty.", i);
+      }
     }
   }
 
@@ -398,14 +396,10 @@ where
 
             info!(" {:6} |T| Saving snapshot ... ({:?})", epoch_start, snapshot_elapsed);
 
-            for i in 0..system.agents.len() {
-              system.agents[i].p_behaviors.snapshot_store();
-              system.agents[i].q_transitions.snapshot_store();
+            for agent in system.agents.iter_mut() {
+              agent.snapshot_store();
             }
 
-            let mut conn = redis.get_connection().unwrap();
-            let _: () = redis::cmd("BGSAVE").query(&mut conn).unwrap();
-
             snapshot_start = Instant::now();
           } else {
             networks_send(worker, epoch_start, &mut system, true, false);
@@ -73,16 +73,18 @@ where
   use anna_model::Money;
 
   use anna_simulation::{
-    perf::run_benchmark,
+    exploit::{exploitability_tree, players_policy, strategy_collect},
+    perf::{duplicate_rates, RateStats},
     players::*,
     players_kuhn::Kuhn2,
     players_lex::Lex,
     players_mul::{PlayersMul2, PlayersMulX},
+    tree::Tree,
     Sim,
   };
   use nnet::Network;
 
-  use anna_utils::{bincode, math, random};
+  use anna_utils::{bincode, random};
 
   let (tx, rx): (Sender<Event>, Receiver<Event>) = mpsc::channel();
 
@@ -183,7 +185,25 @@ where
         benchmark_players.push(players);
       }
 
-      let mut rate_max = -1_000_000.0;
+      let mut stats_best: Option<RateStats> = None;
+
+      // Exploitability of the average policy, only tractable on small 2-player profiles. The game
+      // tree is built once, each measure only collects the strategy of the networks.
+      let ref exploit_funds = vec![fund_init; plan.profile.players];
+      let exploitable = match plan.profile.id.as_str() {
+        "kuhn" | "leduc" | "leduc_french" => plan.profile.players == 2,
+        _ => false,
+      };
+      let exploit_tree = match plan.exploitability {
+        Some(_) if exploitable => match Tree::build(sim, &plan.eval, exploit_funds, 0) {
+          Ok(tree) => Some(tree),
+          Err(e) => {
+            warn!("Exploitability tree not built, measures disabled: {:?}", e);
+            None
+          }
+        },
+        _ => None,
+      };
 
       loop {
         match rx.recv().unwrap() {
@@ -198,19 +218,23 @@ where
 
             // Benchmark
             if action_benchmark {
-              let results: Vec<Vec<(usize, f32)>> = benchmark_players
+              let results: Vec<Vec<(usize, Vec<f32>)>> = benchmark_players
                 .par_iter_mut()
                 .map(|players| {
+                  let ref mut rng = rand::thread_rng();
+
                   networks
                     .iter()
                     .map(|(i, (_, network))| {
-                      let funds =
+                      let funds: Vec<_> =
                         (0..plan.profile.players).map(|seat_id| (seat_id, fund_init)).collect();
 
                       ((players.0).0).reset(network, Policy::P).unwrap();
 
-                      let rates = run_benchmark(sim, &plan.eval, &funds, 4096 / threads, players);
-                      (*i, rates[0])
+                      let deals = (4096 / threads / funds.len()).max(1);
+                      let deals_rates =
+                        duplicate_rates(rng, sim, &plan.eval, &funds, deals, players);
+                      (*i, deals_rates.iter().map(|rates| rates[0]).collect())
                     })
                     .collect()
                 })
@@ -219,27 +243,29 @@ where
               let mut network_rates = vec![Vec::new(); networks.len()];
 
               for rates in results {
-                for (i, rate) in rates {
-                  network_rates[i].push(rate);
+                for (i, xs) in rates {
+                  network_rates[i].extend(xs);
                 }
               }
 
               let mut bench_summary = String::new();
 
-              let mut rates = vec![0.0; networks.len()];
+              let mut stats = Vec::with_capacity(networks.len());
 
               for (i, xs) in network_rates.iter().enumerate() {
-                rates[i] = math::mean(xs);
-                bench_summary.push_str(format!(" {:08.0}", rates[i]).as_str());
+                stats.push(RateStats::new(xs));
+                let (low, high) = stats[i].interval();
+                bench_summary
+                  .push_str(format!(" {:08.0} [{:.0}, {:.0}]", stats[i].mean, low, high).as_str());
               }
 
-              // Check if network is the best so far
+              // Check if network is significantly better than the best so far
               let mut network_best = None;
               for (i, (network_p, network_q)) in networks.iter() {
-                if rate_max < rates[*i] {
+                if stats_best.map(|best| stats[*i].is_better(&best)).unwrap_or(true) {
                   network_best = Some(*i);
-                  rate_max = rates[*i];
-                  debug!("Saving best network {:6} @ {:08.0} ...", i, rates[*i]);
+                  stats_best = Some(stats[*i]);
+                  debug!("Saving best network {:6} @ {} ...", i, stats[*i]);
                   let ref network_file = path_training.join("network-p.data");
                   bincode::serialize_into_file(network_file, &network_p).unwrap();
                   let ref network_file = path_training.join("network-q.data");
@@ -251,6 +277,28 @@ where
                 bench_summary.push_str(format!(" BEST@{}", i).as_str());
               }
 
+              // Exploitability, every few epochs
+              let exploit_now = plan.exploitability.map(|n| epoch % n.max(1) == 0).unwrap_or(false);
+              if let (Some(tree), true) = (exploit_tree.as_ref(), exploit_now) {
+                let episode = &mut (benchmark_players[0].0).0;
+
+                for (i, (network_p, _)) in networks.iter() {
+                  if let Err(e) = episode.reset(network_p, Policy::P) {
+                    warn!("Exploitability of network {} not measured: {:?}", i, e);
+                    continue;
+                  }
+                  match strategy_collect(sim, &plan.eval, exploit_funds, 0, |state| {
+                    players_policy(episode, state)
+                  }) {
+                    Ok(strategy) => {
+                      let e = exploitability_tree(tree, &strategy, sim.blind_biggest);
+                      bench_summary.push_str(format!(" X{}@{:.1}", i, e.mbb).as_str());
+                    }
+                    Err(e) => warn!("Exploitability of network {} not measured: {:?}", i, e),
+                  }
+                }
+              }
+
               // Logging
               info!(" {:6} |W|", epoch);
               info!(" {:6} |W| {}", epoch, bench_summary);
@@ -34,7 +34,11 @@ use anna_model::{
 };
 
 use anna_simulation::{
-  engine, players::Players, players_sel::PlayersSel, rounds::seat_next, Event, Sim, State,
+  engine,
+  players::Players,
+  players_sel::PlayersSel,
+  rounds::{seat_next, seat_previous},
+  Event, Sim, State,
 };
 
 use anna_utils as utils;
@@ -312,7 +316,7 @@ fn game_run<
 
       ui::log(windows.console, "");
 
-      let (_, ref winners_seats, _) =
+      let (finalists_seats, ref winners_seats, _) =
         engine::game_finish(evaluator, &player_ui.state.table_cards, &player_states);
 
       let winners: Vec<usize> = winners_seats
@@ -322,13 +326,38 @@ fn game_run<
 
       ui::log(windows.console, format!("winners: {:?}", winners));
 
+      // Map finalists and states from seats to players.
+      let finalists: Vec<(usize, f32)> = finalists_seats
+        .iter()
+        .map(|&(seat_id, _, _, score)| (player_ui.state.players.player_at(seat_id).unwrap(), score))
+        .collect();
+
+      let mut states = vec![State::Off; SEATS];
+      for (seat_id, state) in player_states.iter().enumerate() {
+        if let Some(player_id) = player_ui.state.players.player_at(seat_id) {
+          states[player_id] = state.clone();
+        }
+      }
+
+      // Dealer of the game (seat before the small blind, as in the engine), as a player.
+      let dealer = {
+        let seat_id = seat_previous(player_states.len(), blind_small_seat_id);
+        player_ui.state.players.player_at(seat_id).unwrap()
+      };
+
       // Update funds.
-      engine::funds_update(
+      let pots = engine::funds_update(
         &sim,
         &mut player_ui.state.player_funds,
         &player_ui.state.player_pots,
-        &winners,
+        &states,
+        &finalists,
+        dealer,
       );
+
+      for pot in pots {
+        ui::log(windows.console, format!("pot: {} {:?}", pot.amount, pot.awards));
+      }
     }
   };
 
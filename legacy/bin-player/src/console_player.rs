@@ -2,11 +2,14 @@ extern crate anna_eval;
 extern crate anna_model;
 extern crate anna_simulation;
 
+use std::time::Instant;
+
 use anna_eval::Eval;
 use anna_model::{cards::Card, money::Money};
 use anna_simulation::{
+  engine::Score,
   players::{Players, Table},
-  Event,
+  Event, SeatId,
 };
 
 use ui;
@@ -90,6 +93,14 @@ where
       events,
     )
   }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.state.players.game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.state.players.deadline(seat_id, deadline)
+  }
 }
 
 impl<'a, P: Players<()>, GS, RS, IN, PL> Table<ui::Exit> for PlayerUI<'a, P, GS, RS, IN, PL>
@@ -13,28 +13,40 @@ use qnet::{QNet, QState};
 use reward::{Reward, RewardF};
 
 use agent_episode::{EpisodeRecord, Policy};
-use data::sample_indices;
-use data_redis::{CBuffer, RSampler, RedisStore};
+use behavior::Behavior;
+use data::{sample_indices, PSampler, Priority};
+use replay::{CBuffer, RSampler, ReplayStore};
 
-pub struct Agent<'a> {
+/** Transition from `s_t` by `a_t`, with the legal actions of `s_t`, whether `a_t` was exploratory
+ ** and the reward of the episode on its last transition. The transitions of an episode are stored
+ ** in a row, the returns reading the next states from the following ones.
+ **/
+pub type Transition = (QState, u8, Vec<u8>, bool, Option<f32>);
+
+pub struct Agent<'a, SP, SQ> {
   pub episodes: usize,
   pub iterations: usize,
 
   pub p_network: tasks::Train<'a, f32, f32>,
-  pub p_behaviors: RSampler<(QState, u8)>, // (s, a)
+  pub p_behaviors: RSampler<Behavior, SP>,
   pub p_steps: usize,
 
   pub q_network: tasks::Train<'a, f32, f32>,
   pub q_network_target: tasks::Predict<'a, f32, f32>,
 
   pub q_network_updates: usize,
-  pub q_transitions: CBuffer<(QState, u8, f32, Option<QState>)>, // (s, a, r, s')
+  pub q_transitions: CBuffer<Transition, SQ>,
+  pub q_priorities: Option<PSampler>, // Prioritised replay of the transitions
   pub q_steps: usize,
 }
-unsafe impl<'a> Send for Agent<'a> {}
-unsafe impl<'a> Sync for Agent<'a> {}
-
-impl<'a> Agent<'a> {
+unsafe impl<'a, SP, SQ> Send for Agent<'a, SP, SQ> {}
+unsafe impl<'a, SP, SQ> Sync for Agent<'a, SP, SQ> {}
+
+impl<'a, SP, SQ> Agent<'a, SP, SQ>
+where
+  SP: ReplayStore<Behavior>,
+  SQ: ReplayStore<Transition>,
+{
   pub fn policy_sample<R: Rng>(&self, rng: &mut R, params: &AgentParams) -> Policy {
     if rng.gen::<f32>() < params.anticipation {
       Policy::Q(params.exploration.apply(self.iterations))
@@ -43,6 +55,28 @@ impl<'a> Agent<'a> {
     }
   }
 
+  pub fn snapshot_store(&mut self) -> () {
+    self.p_behaviors.snapshot_store();
+    self.q_transitions.snapshot_store();
+  }
+
+  /** Restore the replay memories of the last snapshot, `false` without any.
+   **
+   ** Priorities are not kept, the transitions being given the highest one again.
+   **/
+  pub fn snapshot_load(&mut self) -> bool {
+    let p_loaded = self.p_behaviors.snapshot_load();
+    let q_loaded = self.q_transitions.snapshot_load();
+
+    if let Some(ref mut priorities) = self.q_priorities {
+      for i in 0..self.q_transitions.len() {
+        priorities.push(i);
+      }
+    }
+
+    p_loaded && q_loaded
+  }
+
   pub fn update<R: Rng>(
     &mut self,
     rng: &mut R,
@@ -54,45 +88,69 @@ impl<'a> Agent<'a> {
     records: &Vec<(EpisodeRecord, Reward)>,
   ) -> Result<(Option<(f32, f32, f32)>, Option<(f32, f32, f32)>, bool), nnet::Error> {
     use behavior::BehaviorSet;
-    use nnet::tensor_of;
+    use nnet::{tensor1, tensor_of};
 
-    // Compute long term reward per transition using the target network.
-    fn transitions_rewards_l(
+    // Compute the return per transition, mixing its n-step returns as given by `returns`. Next
+    // states are valued by double DQN, the best legal action being selected by the online network
+    // and evaluated by the target one.
+    fn transitions_returns<S: ReplayStore<Transition>>(
       qnet: &QNet,
       actions_encoder: &ActionsEncoder,
       cards_encoder: &mut CardsEncoder,
+      q_train: &mut tasks::Train<f32, f32>,
       q_predict: &mut tasks::Predict<f32, f32>,
-      transitions: &mut CBuffer<(QState, u8, f32, Option<QState>)>,
+      transitions: &mut CBuffer<Transition, S>,
       indices: &[usize],
+      returns: &QReturns,
+      discount: f32,
     ) -> Result<Vec<f32>, nnet::Error> {
-      let mut is: Vec<Option<usize>> = Vec::new();
+      let horizon = returns.horizon();
+
+      let mut steps: Vec<(Vec<f32>, Vec<f32>, Vec<Option<usize>>)> = Vec::new();
       let mut ss: Vec<Vec<f32>> = Vec::new();
+      let mut masks: Vec<Vec<u8>> = Vec::new();
 
       for &i in indices {
-        match transitions.get(i).3 {
-          Some(ref s) => {
+        // Transitions of the episode from `i`, up to the last state the returns can bootstrap on.
+        let mut episode = vec![transitions.get(i)];
+        while episode.len() <= horizon && episode.last().unwrap().4.is_none() {
+          let j = (i + episode.len()) % transitions.size;
+          episode.push(transitions.get(j));
+        }
+
+        let explored: Vec<bool> = episode.iter().take(horizon).map(|t| t.3).collect();
+        let n = q_steps(returns, &explored);
+        let weights = returns.weights(n);
+
+        let rewards = (0..n).map(|k| episode[k].4.unwrap_or(0.0)).collect();
+
+        let mut is: Vec<Option<usize>> = Vec::new();
+        for k in 1..n + 1 {
+          if k < episode.len() && weights[k - 1] > 0.0 {
+            let (ref s, _, ref mask, _, _) = episode[k];
             is.push(Some(ss.len()));
-            let xs = s.to_vector(qnet, actions_encoder, cards_encoder);
-            ss.push(xs);
-          }
-          None => {
+            ss.push(s.to_vector(qnet, actions_encoder, cards_encoder));
+            masks.push(mask.clone());
+          } else {
             is.push(None);
           }
         }
+
+        steps.push((weights, rewards, is));
       }
 
-      if ss.len() > 0 {
-        let vs = qnet.run_all_max(q_predict, &ss)?;
+      let vs =
+        if ss.len() > 0 { qnet.run_all_best(q_train, q_predict, &ss, &masks)? } else { Vec::new() };
 
-        let mut rs: Vec<f32> = Vec::new();
-        for i in 0..indices.len() {
-          rs.push(is[i].map(|j| vs[j]).unwrap_or(0.0));
-        }
+      let gs = steps
+        .iter()
+        .map(|&(ref weights, ref rewards, ref is)| {
+          let values: Vec<Option<f32>> = is.iter().map(|i| i.map(|j| vs[j])).collect();
+          q_return(weights, rewards, &values, discount)
+        })
+        .collect();
 
-        Ok(rs)
-      } else {
-        Ok(vec![0.0; is.len()])
-      }
+      Ok(gs)
     }
 
     self.episodes += records.len();
@@ -103,31 +161,27 @@ impl<'a> Agent<'a> {
       let behaviors_len = record.behaviors.len();
 
       if behaviors_len > 0 {
-        // Extract and store transitions.
-        let transitions: Vec<(QState, u8, f32, Option<QState>)> = record
+        // Store the transitions of the episode in a row, the last one with the reward.
+        let transitions: Vec<Transition> = record
           .behaviors
           .iter()
           .enumerate()
-          .map(|(i, &(ref state, action, _))| {
-            let reward_i = if i == (behaviors_len - 1) { reward } else { 0.0 };
-
-            let state_next = {
-              if i == behaviors_len - 1 {
-                None
-              } else {
-                let (ref s, _, _) = record.behaviors[i + 1];
-                Some(s.clone())
-              }
-            };
-
-            (state.clone(), action, reward_i, state_next)
+          .map(|(i, &(ref state, action, explore, ref mask))| {
+            let reward = if i == behaviors_len - 1 { Some(reward) } else { None };
+            (state.clone(), action, mask.clone(), explore, reward)
           })
           .collect();
 
         let transitions_len = transitions.len();
-        self.q_transitions.push(transitions);
+        let pushed = self.q_transitions.push(transitions);
         self.q_steps += transitions_len;
 
+        if let Some(ref mut priorities) = self.q_priorities {
+          for i in pushed {
+            priorities.push(i);
+          }
+        }
+
         match record.policy {
           Policy::P => {
             // NOOP
@@ -135,7 +189,7 @@ impl<'a> Agent<'a> {
           Policy::Q(_) => {
             // Feed the reservoir
             let xs =
-              record.behaviors.iter().map(|&(ref is, action, _)| (is.clone(), action)).collect();
+              record.behaviors.iter().map(|&(ref is, action, _, _)| (is.clone(), action)).collect();
 
             self.p_steps += self.p_behaviors.push(rng, xs);
           }
@@ -176,6 +230,7 @@ impl<'a> Agent<'a> {
         params.p_rate.apply(self.iterations),
         &training.inputs,
         &training.outputs,
+        None,
       )?)
     } else {
       None
@@ -188,16 +243,26 @@ impl<'a> Agent<'a> {
       self.q_steps = 0;
       self.q_network_updates += 1;
 
-      let indices = sample_indices(rng, self.q_transitions.len(), params.batch_size);
+      let (indices, weights) = match self.q_priorities {
+        Some(ref mut priorities) => {
+          let beta = params.q_replay.beta(self.iterations);
+          let (indices, weights) = priorities.sample(rng, params.batch_size, beta);
+          (indices, Some(weights))
+        }
+        None => (sample_indices(rng, self.q_transitions.len(), params.batch_size), None),
+      };
       let size = indices.len();
 
-      let rls: Vec<f32> = transitions_rewards_l(
+      let rs: Vec<f32> = transitions_returns(
         qnet,
         actions_encoder,
         cards_encoder,
+        &mut self.q_network,
         &mut self.q_network_target,
         &mut self.q_transitions,
         &indices,
+        &params.q_returns,
+        params.discount_factor.apply(self.iterations),
       )?;
 
       let mut is = Vec::new();
@@ -209,14 +274,15 @@ impl<'a> Agent<'a> {
 
       let mut os: Tensor<f32> = self.q_network.predict(&is)?;
 
+      let mut errors = Vec::new();
+
       let training = {
         for (i, &t_i) in indices.iter().enumerate() {
-          let (_, action, ri, _) = self.q_transitions.get(t_i);
-
-          let rl = rls[i];
-          let r = ri + (params.discount_factor.apply(self.iterations) * rl);
+          let action = self.q_transitions.get(t_i).1;
+          let r = rs[i];
 
           let actions_size = qnet.action_class.size();
+          errors.push(r - os[action as usize + (actions_size * i)]);
           for a in 0..actions_size {
             if a == action as usize {
               os[a + (actions_size * i)] = r;
@@ -227,6 +293,15 @@ impl<'a> Agent<'a> {
         BehaviorSet { inputs: is, outputs: os, size: size }
       };
 
+      if let Some(ref mut priorities) = self.q_priorities {
+        priorities.update(&indices, &errors);
+      }
+
+      let weights = match weights {
+        Some(ref ws) => Some(tensor1(ws)?),
+        None => None,
+      };
+
       self.q_network.context.optimizer_reset()?;
 
       Some(QNet::train_many(
@@ -235,6 +310,7 @@ impl<'a> Agent<'a> {
         params.q_rate.apply(self.iterations),
         &training.inputs,
         &training.outputs,
+        weights.as_ref(),
       )?)
     } else {
       None
@@ -269,4 +345,87 @@ pub struct AgentParams {
   pub q_rate: HParam,
 
   pub ramping: usize, // Number of epochs used to bootstrap the agent memory (no training)
+
+  pub q_replay: QReplay,   // Sampling of the transitions
+  pub q_returns: QReturns, // Targets of the transitions
+}
+
+#[derive(Clone)]
+pub enum QReplay {
+  Uniform,
+  // Importance-sampling weights corrected by `beta`, annealed towards 1.
+  Prioritised { priority: Priority, alpha: f32, beta: HParam, epsilon: f32 },
+}
+
+impl QReplay {
+  pub fn beta(&self, iterations: usize) -> f32 {
+    match self {
+      &QReplay::Uniform => 0.0,
+      &QReplay::Prioritised { ref beta, .. } => beta.apply(iterations),
+    }
+  }
+}
+
+#[derive(Clone)]
+pub enum QReturns {
+  // Rewards of the n next steps, bootstrapped on the n-th next state (1 being one-step Q-learning).
+  NStep(usize),
+  // λ-return truncated at `horizon` steps, 0 being one-step and 1 Monte Carlo returns.
+  // Both are cut at the next exploratory action (`q_steps`).
+  Lambda { lambda: f32, horizon: usize },
+}
+
+impl QReturns {
+  pub fn horizon(&self) -> usize {
+    match self {
+      &QReturns::NStep(n) => n.max(1),
+      &QReturns::Lambda { horizon, .. } => horizon.max(1),
+    }
+  }
+
+  /** Weights of the 1 .. n-step returns of a transition followed by `n` steps, the n-step return
+   ** taking the weight of the longer ones (truncated, cut or past the end of the episode).
+   **/
+  pub fn weights(&self, n: usize) -> Vec<f32> {
+    match self {
+      &QReturns::NStep(_) => (0..n).map(|k| if k == n - 1 { 1.0 } else { 0.0 }).collect(),
+      &QReturns::Lambda { lambda, .. } => (0..n)
+        .map(|k| {
+          let w = lambda.powi(k as i32);
+          if k == n - 1 {
+            w
+          } else {
+            (1.0 - lambda) * w
+          }
+        })
+        .collect(),
+    }
+  }
+}
+
+/** Steps the returns of a transition run over, `explored` telling whether the actions from its
+ ** own one were exploratory: up to the horizon, the end of the episode or the next exploratory
+ ** action (Watkins's Q(λ)), the returns bootstrapping on the state it was taken from.
+ **/
+pub fn q_steps(returns: &QReturns, explored: &[bool]) -> usize {
+  let n = returns.horizon().min(explored.len());
+  (1..n).find(|&k| explored[k]).unwrap_or(n)
+}
+
+/** Return of a transition, mixing its 1 .. n-step returns by `weights`. `rewards` are the rewards
+ ** `r_t .. r_t+n-1` and `values` the ones of the states `s_t+1 .. s_t+n`, `None` when terminal.
+ **/
+pub fn q_return(weights: &[f32], rewards: &[f32], values: &[Option<f32>], discount: f32) -> f32 {
+  let mut g = 0.0;
+  let mut r = 0.0; // Discounted rewards up to the k-th step
+  let mut d = 1.0; // 𝛾^k
+  for k in 0..rewards.len() {
+    r += d * rewards[k];
+    d *= discount;
+    if weights[k] > 0.0 {
+      g += weights[k] * (r + d * values[k].unwrap_or(0.0));
+    }
+  }
+
+  g
 }
@@ -18,8 +18,6 @@ use anna_utils::random;
 use qnet::{QNet, QState};
 use reward::{Reward, RewardF};
 
-use data::{sample_indices, CBuffer, RSampler};
-
 // This is pretty limited, as it works only for plan that have constant input size
 pub fn players_create<'a, CE: CardsEncoder + Send + Sync, F>(
   networks_path: &Path,
@@ -108,24 +106,9 @@ impl<'a, R: Rng, CE: CardsEncoder> Episode<'a, R, CE> {
 
     context.set(network)
   }
-}
-
-impl<'a, R: Rng, CE: CardsEncoder> Players<()> for Episode<'a, R, CE> {
-  fn init(
-    &mut self,
-    blinds: &[Money],
-    _: SeatId,
-    _: &[Money],
-    playing_hands: &Vec<(usize, Vec<Card>)>,
-  ) -> () {
-    assert!(playing_hands.len() == 1);
-    self.blind_biggest = *blinds.iter().max().unwrap();
-    for &(seat_id, ref cards) in playing_hands {
-      self.qstate = QState::new(self.qnet, seat_id, cards);
-    }
-  }
 
-  fn play(
+  // Network output along the legal actions mask.
+  fn predict(
     &mut self,
     round_id: usize,
     table_target: Money,
@@ -134,10 +117,8 @@ impl<'a, R: Rng, CE: CardsEncoder> Players<()> for Episode<'a, R, CE> {
     seat_id: usize,
     player_fund: Money,
     events: &[Event],
-  ) -> Result<u8, ()> {
-    use anna_utils::math::sample;
+  ) -> (Vec<f32>, HashSet<usize>) {
     use nnet::tensor1;
-    use std::cmp::Ordering;
 
     assert!(self.qstate.player == seat_id);
 
@@ -168,12 +149,48 @@ impl<'a, R: Rng, CE: CardsEncoder> Players<()> for Episode<'a, R, CE> {
       player_pots[seat_id],
     );
 
-    let (i, _) = probs
-      .iter()
-      .enumerate()
-      .filter(|&(i, _)| probs_mask.contains(&i))
-      .max_by(|&(_, a), &(_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
-      .expect("At least one probs should be > 0.");
+    (probs.to_vec(), probs_mask)
+  }
+}
+
+impl<'a, R: Rng, CE: CardsEncoder> Players<()> for Episode<'a, R, CE> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    _: SeatId,
+    _: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    assert!(playing_hands.len() == 1);
+    self.blind_biggest = *blinds.iter().max().unwrap();
+    for &(seat_id, ref cards) in playing_hands {
+      self.qstate = QState::new(self.qnet, seat_id, cards);
+    }
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    use anna_utils::math::sample;
+
+    let (probs, probs_mask) = self.predict(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    );
+
+    let i = action_best(&probs, &probs_mask);
 
     let explore = match self.record.policy {
       Policy::P => false,
@@ -193,16 +210,76 @@ impl<'a, R: Rng, CE: CardsEncoder> Players<()> for Episode<'a, R, CE> {
       i as u8
     };
 
-    self.record.behaviors.push((self.qstate.clone(), action, explore));
+    let mut mask: Vec<u8> = probs_mask.iter().map(|&j| j as u8).collect();
+    mask.sort();
+
+    self.record.behaviors.push((self.qstate.clone(), action, explore, mask));
 
     Ok(action)
   }
+
+  // The best action, the other legal ones sharing the exploration rate of the `Q` policy.
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    let qstate = self.qstate.clone();
+    let (probs, probs_mask) = self.predict(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    );
+    self.qstate = qstate;
+
+    let i = action_best(&probs, &probs_mask);
+    let others = probs_mask.len() - 1;
+    let exploration = match self.record.policy {
+      Policy::Q(exploration) if others > 0 => exploration,
+      _ => 0.,
+    };
+
+    (0..probs.len())
+      .map(|j| {
+        if j == i {
+          1. - exploration
+        } else if probs_mask.contains(&j) {
+          exploration / others as f32
+        } else {
+          0.
+        }
+      })
+      .collect()
+  }
+}
+
+// Legal action of highest output.
+fn action_best(probs: &[f32], probs_mask: &HashSet<usize>) -> usize {
+  use std::cmp::Ordering;
+
+  let (i, _) = probs
+    .iter()
+    .enumerate()
+    .filter(|&(i, _)| probs_mask.contains(&i))
+    .max_by(|&(_, a), &(_, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+    .expect("At least one probs should be > 0.");
+
+  i
 }
 
 #[derive(Clone)]
 pub struct EpisodeRecord {
   pub policy: Policy,
-  pub behaviors: Vec<(QState, u8, bool)>,
+  pub behaviors: Vec<(QState, u8, bool, Vec<u8>)>, // (s, a, explored, legal actions of s)
 }
 
 #[derive(Clone)]
@@ -3,12 +3,11 @@ extern crate cpuprofiler;
 use self::cpuprofiler::PROFILER;
 
 use rand::{Rng, StdRng};
-use redis::Client;
 use std::path::Path;
 
 use nnet::{self, Network};
 
-use agent::{Agent, AgentParams};
+use agent::{Agent, AgentParams, QReplay, Transition};
 use agent_episode::Episode;
 use anna_eval::Eval;
 use anna_model::{
@@ -16,21 +15,26 @@ use anna_model::{
   Money,
 };
 use anna_simulation::{perf::Rate, players_mul::PlayersMulX, Sim};
+use behavior::Behavior;
 use qnet::QNet;
+use replay::{CBuffer, MemoryStore, RSampler, ReplayStore};
 use reward::RewardF;
 
-use data::CBuffer;
-
 // #[derive(Clone)]
-pub struct AgentSystem<'a> {
-  pub agents: Vec<Agent<'a>>,
-  pub agents_hist: Vec<CBuffer<(Network, Network)>>,
+pub struct AgentSystem<'a, SP, SQ> {
+  pub agents: Vec<Agent<'a, SP, SQ>>,
+  pub agents_hist: Vec<CBuffer<(Network, Network), MemoryStore<(Network, Network)>>>,
   pub agents_hist_sampling: f32,
 }
 
-impl<'a> AgentSystem<'a> {
-  pub fn new(
-    redis_client: &Client,
+impl<'a, SP, SQ> AgentSystem<'a, SP, SQ>
+where
+  SP: ReplayStore<Behavior>,
+  SQ: ReplayStore<Transition>,
+{
+  // `stores` gives the stores of the behaviors and transitions of an agent.
+  pub fn new<F>(
+    stores: F,
     qnet: &'a QNet,
     agents_size: usize,
     agents_hist_size: usize,
@@ -38,15 +42,21 @@ impl<'a> AgentSystem<'a> {
     p_reservoir_min: f32,
     p_reservoir_size: usize,
     q_buffer_size: usize,
-  ) -> Result<AgentSystem<'a>, nnet::Error> {
+    q_replay: &QReplay,
+  ) -> Result<AgentSystem<'a, SP, SQ>, nnet::Error>
+  where
+    F: Fn(usize) -> Result<(SP, SQ), nnet::Error>,
+  {
     use nnet::{
       network_init,
       tasks::{Predict, Train},
       NetworkContext, Tensor,
     };
 
-    let agent_init = |i: usize| -> Result<Agent<'a>, nnet::Error> {
-      use data_redis::{CBuffer, RSampler};
+    let agent_init = |i: usize| -> Result<Agent<'a, SP, SQ>, nnet::Error> {
+      use data::PSampler;
+
+      let (p_store, q_store) = stores(i)?;
 
       let mut p_train = qnet.p_train()?;
       let mut q_train = qnet.q_train()?;
@@ -60,23 +70,20 @@ impl<'a> AgentSystem<'a> {
         iterations: 0,
 
         p_network: p_train,
-        p_behaviors: RSampler::new(
-          p_reservoir_size,
-          p_reservoir_min,
-          redis_client,
-          format!("b:{}:", i).as_bytes().to_vec(),
-        ),
+        p_behaviors: RSampler::new(p_reservoir_size, p_reservoir_min, p_store),
         p_steps: 0,
         q_network: q_train,
 
         q_network_target: q_target,
 
         q_network_updates: 0,
-        q_transitions: CBuffer::new(
-          q_buffer_size,
-          redis_client,
-          format!("t:{}:", i).as_bytes().to_vec(),
-        ),
+        q_transitions: CBuffer::new(q_buffer_size, q_store),
+        q_priorities: match q_replay {
+          &QReplay::Uniform => None,
+          &QReplay::Prioritised { priority, alpha, epsilon, .. } => {
+            Some(PSampler::new(priority, q_buffer_size, alpha, epsilon))
+          }
+        },
         q_steps: 0,
       })
     };
@@ -86,7 +93,8 @@ impl<'a> AgentSystem<'a> {
       agents.push(agent_init(i)?);
     }
 
-    let agents_hist = agents.iter().map(|agent| CBuffer::new(agents_hist_size)).collect();
+    let agents_hist =
+      agents.iter().map(|agent| CBuffer::new(agents_hist_size, MemoryStore::new())).collect();
 
     Ok(AgentSystem {
       agents: agents,
@@ -207,8 +215,9 @@ impl<'a> AgentSystem<'a> {
       // let games_per_worker = usize::min(1, remainings / workers);
       let actives = usize::min(remainings, workers);
 
-      let scores: Vec<(Vec<usize>, usize, Vec<(usize, usize)>, Vec<EpisodeRecord>, Score)> =
-        pool_players.par_iter_mut()
+      let games_run: Vec<
+        Option<(Vec<usize>, usize, Vec<(usize, usize)>, Vec<EpisodeRecord>, Score)>,
+      > = pool_players.par_iter_mut()
         // pool_players.iter_mut()
                     .take(actives)
                     .enumerate()
@@ -225,7 +234,7 @@ impl<'a> AgentSystem<'a> {
                            rng.shuffle(&mut agents);
 
                            for agent_id in agents {
-                             let hist_len = self.agents_hist[agent_id].values.len();
+                             let hist_len = self.agents_hist[agent_id].values.values().len();
                              let mut sample = |start| {
                                let end = ((hist_len + 1) as f32 * (1.0 - self.agents_hist_sampling))
                                          as usize;
@@ -277,9 +286,8 @@ impl<'a> AgentSystem<'a> {
                              let policy = agent.policy_sample(rng, params);
 
                              if hist_id > 0 {
-                               let hist_len = self.agents_hist[agent_id].values.len();
                                let (ref p_network, ref q_network) =
-                                 self.agents_hist[agent_id].values[hist_len - hist_id];
+                                 *self.agents_hist[agent_id].get_age(hist_id);
 
                                let network = match episode.record.policy {
                                  Policy::P => p_network.clone(),
@@ -297,24 +305,31 @@ impl<'a> AgentSystem<'a> {
                              }
                            }
 
-                           // Run game.
-                           let (_, score) = table_game_simulate(rng,
-                                                                sim,
-                                                                eval,
-                                                                players,
-                                                                player_funds,
-                                                                player_first).unwrap();
-
-                           (seats,
-                            candidate,
-                            opponents,
-                            players.0.iter().map(|(p, _)| p.record.clone()).collect(),
-                            score)
+                           // Run game, a failed one being dropped.
+                           let score = match table_game_simulate(rng,
+                                                                 sim,
+                                                                 eval,
+                                                                 players,
+                                                                 player_funds,
+                                                                 player_first) {
+                             Ok((_, score)) => score,
+                             Err((_, e)) => {
+                               error!("Game dropped, worker {}: {:?}", worker_id, e);
+                               return None;
+                             }
+                           };
+
+                           Some((seats,
+                                 candidate,
+                                 opponents,
+                                 players.0.iter().map(|(p, _)| p.record.clone()).collect(),
+                                 score))
                          })
                     .collect();
 
-      // Increment games count
-      games += scores.len();
+      // Increment games count, dropped games included.
+      games += games_run.len();
+      let scores = games_run.into_iter().filter_map(|x| x);
 
       // Compute episodes rewards and group per agents
       let mut agent_episodes = vec![Vec::new(); self.agents.len()];
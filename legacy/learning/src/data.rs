@@ -8,115 +8,184 @@ pub fn sample_indices<R: Rng>(rng: &mut R, len: usize, amount_: usize) -> Vec<us
   sample_indices(rng, len, amount)
 }
 
-/** Circular Buffer
+/** Sum tree, each node keeping the sum of the priorities of its leaves.
+ **  Updating a priority and finding the leaf at a given mass are O(log n).
  **/
 #[derive(Clone)]
-pub struct CBuffer<A> {
-  pub size: usize,
-  pub values: Vec<A>,
-  i: usize,
+pub struct SumTree {
+  leaves: usize,
+  nodes: Vec<f32>, // Root at 1, children of `k` at `2k` and `2k + 1`, leaves from `leaves`.
 }
 
-impl<A> CBuffer<A> {
-  pub fn new(size: usize) -> CBuffer<A> {
-    CBuffer { size: size, values: Vec::with_capacity(size), i: 0 }
+impl SumTree {
+  pub fn new(size: usize) -> SumTree {
+    let leaves = usize::max(1, size).next_power_of_two();
+    SumTree { leaves: leaves, nodes: vec![0.0; 2 * leaves] }
   }
 
-  pub fn get(&self, i: usize) -> &A {
-    &self.values[i]
+  pub fn total(&self) -> f32 {
+    self.nodes[1]
   }
 
-  pub fn push(&mut self, values: Vec<A>) -> () {
-    for value in values {
-      let size = self.values.len();
-      if size == self.size {
-        if self.i == self.size {
-          self.i = 0;
-        }
-        self.values[self.i] = value;
-      } else {
-        self.values.push(value);
-      }
+  pub fn get(&self, i: usize) -> f32 {
+    self.nodes[self.leaves + i]
+  }
+
+  pub fn set(&mut self, i: usize, priority: f32) -> () {
+    let mut k = self.leaves + i;
+    self.nodes[k] = priority;
 
-      self.i += 1;
+    // Sums are recomputed rather than shifted, rounding errors not piling up.
+    while k > 1 {
+      k /= 2;
+      self.nodes[k] = self.nodes[2 * k] + self.nodes[2 * k + 1];
     }
   }
 
-  pub fn usage(&self) -> f32 {
-    let size = self.values.len();
-    if size == self.size {
-      1.0
-    } else {
-      size as f32 / self.size as f32
+  // Leaf at which the cumulated priorities reach `mass`, `mass` being below `total`.
+  pub fn find(&self, mass: f32) -> usize {
+    let mut mass = mass;
+    let mut k = 1;
+
+    while k < self.leaves {
+      let left = self.nodes[2 * k];
+      if mass < left || self.nodes[2 * k + 1] == 0.0 {
+        k = 2 * k;
+      } else {
+        mass -= left;
+        k = 2 * k + 1;
+      }
     }
-  }
 
-  pub fn len(&self) -> usize {
-    self.values.len()
+    k - self.leaves
   }
 }
 
-/** Reservoir Sampling (incremental), Algorithm R
- **  https://en.wikipedia.org/wiki/Reservoir_sampling
- **/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Priority {
+  Proportional, // (|δ| + ε)^α
+  Rank,         // (1 / rank(|δ|))^α
+}
 
+/** Prioritised Sampling, of the values of a buffer by their last error
+ **  https://arxiv.org/abs/1511.05952
+ **
+ ** A value pushed gets the highest priority, being sampled at least once before its error is
+ ** known. The ranks are recomputed once as many errors as values were updated, a value keeping its
+ ** rank until then.
+ **/
 #[derive(Clone)]
-pub struct RSampler<A> {
-  pub size: usize,
-  values: Vec<A>,
-  p_min: f32,
-  i: usize,
+pub struct PSampler {
+  pub priority: Priority,
+  alpha: f32,
+  epsilon: f32,
+  tree: SumTree,
+  errors: Vec<f32>, // |δ| per value
+  error_max: f32,
+  updates: usize, // Errors updated since the last ranking
 }
 
-impl<A> RSampler<A> {
-  pub fn new(size: usize, p_min: f32) -> RSampler<A> {
-    RSampler { size: size, values: Vec::with_capacity(size), p_min: p_min, i: 0 }
+impl PSampler {
+  pub fn new(priority: Priority, size: usize, alpha: f32, epsilon: f32) -> PSampler {
+    PSampler {
+      priority: priority,
+      alpha: alpha,
+      epsilon: epsilon,
+      tree: SumTree::new(size),
+      errors: Vec::with_capacity(size),
+      error_max: 1.0,
+      updates: 0,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.errors.len()
   }
 
-  pub fn get(&self, i: usize) -> &A {
-    &self.values[i]
+  // A value was pushed at `i` of the buffer.
+  pub fn push(&mut self, i: usize) -> () {
+    let error = self.error_max;
+    if i == self.errors.len() {
+      self.errors.push(error);
+    } else {
+      self.errors[i] = error;
+    }
+
+    let priority = match self.priority {
+      Priority::Proportional => self.proportional(error),
+      Priority::Rank => 1.0,
+    };
+    self.tree.set(i, priority);
   }
 
-  pub fn push<R: Rng>(&mut self, rng: &mut R, values: Vec<A>) -> usize {
-    use self::rand::distributions::{Distribution, Range};
+  /** Sample `amount` indices, one per segment of equal priority mass, along with their
+   ** importance-sampling weights `(N * P(i))^-β`, normalised by the biggest one of the sample.
+   **/
+  pub fn sample<R: Rng>(
+    &mut self,
+    rng: &mut R,
+    amount: usize,
+    beta: f32,
+  ) -> (Vec<usize>, Vec<f32>) {
+    let len = self.errors.len();
+    if len == 0 || amount == 0 {
+      return (Vec::new(), Vec::new());
+    }
 
-    let mut count = 0;
+    let total = self.tree.total();
+    let segment = total / amount as f32;
 
-    for value in values {
-      let size = self.values.len();
-      if size == self.size {
-        let p = (1.0 / self.i as f32) * (self.size as f32);
-        let p_norm = if p < self.p_min { self.p_min } else { p };
+    let indices: Vec<usize> = (0..amount)
+      .map(|j| {
+        let mass = segment * (j as f32 + rng.gen::<f32>());
+        usize::min(self.tree.find(mass), len - 1)
+      })
+      .collect();
 
-        if rng.gen::<f32>() < p_norm {
-          count += 1;
+    let weights: Vec<f32> =
+      indices.iter().map(|&i| (len as f32 * (self.tree.get(i) / total)).powf(-beta)).collect();
+    let weight_max = weights.iter().cloned().fold(0.0, f32::max);
 
-          let range = Range::new(0, self.size);
-          let j = range.sample(rng);
+    (indices, weights.into_iter().map(|w| w / weight_max).collect())
+  }
 
-          self.values[j] = value;
-        }
-      } else {
-        count += 1;
+  // Errors `δ` of the values at `indices`.
+  pub fn update(&mut self, indices: &[usize], errors: &[f32]) -> () {
+    for (&i, &error) in indices.iter().zip(errors.iter()) {
+      let error = error.abs();
+      self.errors[i] = error;
+      self.error_max = f32::max(self.error_max, error);
 
-        self.values.push(value);
+      if self.priority == Priority::Proportional {
+        let priority = self.proportional(error);
+        self.tree.set(i, priority);
       }
-      self.i += 1;
     }
 
-    count
+    self.updates += indices.len();
+    if self.priority == Priority::Rank && self.updates >= self.errors.len() {
+      self.rank();
+    }
+  }
+
+  fn proportional(&self, error: f32) -> f32 {
+    (error + self.epsilon).powf(self.alpha)
   }
 
-  pub fn usage(&self) -> f32 {
-    let size = self.values.len();
-    if size == self.size {
-      1.0
-    } else {
-      size as f32 / self.size as f32
+  fn rank(&mut self) -> () {
+    use std::cmp::Ordering;
+
+    let mut indices: Vec<usize> = (0..self.errors.len()).collect();
+    {
+      let ref errors = self.errors;
+      indices.sort_by(|&a, &b| errors[b].partial_cmp(&errors[a]).unwrap_or(Ordering::Equal));
     }
-  }
 
-  pub fn len(&self) -> usize {
-    self.values.len()
+    for (rank, i) in indices.into_iter().enumerate() {
+      let priority = (1.0 / (rank + 1) as f32).powf(self.alpha);
+      self.tree.set(i, priority);
+    }
+
+    self.updates = 0;
   }
 }
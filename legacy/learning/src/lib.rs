@@ -22,9 +22,9 @@ pub mod agent_episode;
 pub mod agent_system;
 pub mod behavior;
 pub mod data;
-pub mod data_redis;
 pub mod plan;
 pub mod qnet;
+pub mod replay;
 pub mod reward;
 
 pub mod snet;
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use agent::AgentParams;
+use agent::{AgentParams, QReplay, QReturns, Transition};
 use anna_eval::{strength::*, *};
 use anna_model::{
   cards::Card,
@@ -10,8 +10,11 @@ use anna_model::{
   profile::Profile,
   ActionClass, Money,
 };
-use anna_simulation::Sim;
+use anna_simulation::{OddChip, Sim};
 use anna_utils::random;
+use behavior::Behavior;
+use data::Priority;
+use replay::ReplayStore;
 use reward::RewardF;
 use snet::{CardsSNet, SNet, SNetParams};
 
@@ -28,6 +31,13 @@ pub enum CardsEncoding {
   StrengthNNet,
 }
 
+#[derive(Clone, Eq, PartialEq)]
+pub enum ReplayStorage {
+  Memory,
+  File,          // Segments in the `replay` directory of the training
+  Redis(String), // Url of the instance
+}
+
 #[derive(Clone)]
 pub struct Plan<AC, E> {
   pub action_class: AC,
@@ -56,6 +66,10 @@ pub struct Plan<AC, E> {
   pub p_reservoir_min: f32,
 
   pub q_buffer_size: usize,
+
+  pub replay: ReplayStorage,
+
+  pub exploitability: Option<usize>, // Epochs between exploitability measures
 }
 
 impl<AC, E> Plan<AC, E>
@@ -112,6 +126,40 @@ impl<AC, E> Plan<AC, E> {
     }
   }
 
+  // Stores of the behaviors and transitions of agent `i`.
+  pub fn replay_stores(
+    &self,
+    path_training: &Path,
+    i: usize,
+  ) -> Result<(Box<ReplayStore<Behavior> + Send>, Box<ReplayStore<Transition> + Send>), nnet::Error>
+  {
+    use redis::Client;
+    use replay::{FileStore, MemoryStore, RedisStore};
+
+    let p_store: Box<ReplayStore<Behavior> + Send>;
+    let q_store: Box<ReplayStore<Transition> + Send>;
+
+    match self.replay {
+      ReplayStorage::Memory => {
+        p_store = Box::new(MemoryStore::new());
+        q_store = Box::new(MemoryStore::new());
+      }
+      ReplayStorage::File => {
+        let ref path = path_training.join("replay");
+        let segment_size = 64 << 20;
+        p_store = Box::new(FileStore::new(&path.join(format!("b-{}", i)), segment_size)?);
+        q_store = Box::new(FileStore::new(&path.join(format!("t-{}", i)), segment_size)?);
+      }
+      ReplayStorage::Redis(ref url) => {
+        let ref client = Client::open(url.as_str())?;
+        p_store = Box::new(RedisStore::new(client, format!("b:{}:", i).into_bytes())?);
+        q_store = Box::new(RedisStore::new(client, format!("t:{}:", i).into_bytes())?);
+      }
+    }
+
+    Ok((p_store, q_store))
+  }
+
   pub fn reward_f(&self) -> RewardF {
     RewardF::new(self.delta_biggest())
   }
@@ -124,7 +172,9 @@ impl<AC, E> Plan<AC, E> {
       action_class: &self.action_class,
       blind_biggest: self.blind_biggest(),
       profile: self.profile.clone(),
-      strict: strict
+      strict: strict,
+      odd_chip: OddChip::ButtonLeft,
+      budget: None,
     }
   }
 }
@@ -156,6 +206,8 @@ pub fn plan_kuhn2() -> Plan<ActionKuhn, EvalNaive> {
       p_rate: HParam::new(0.1),
       q_rate: HParam::new(0.1),
       ramping: 0,
+      q_replay: QReplay::Uniform,
+      q_returns: QReturns::NStep(1),
     },
     snet_params: SNetParams { learning_rate: 0.1, batch_size: 64, accuracy: 64 },
 
@@ -172,6 +224,10 @@ pub fn plan_kuhn2() -> Plan<ActionKuhn, EvalNaive> {
     p_reservoir_size: 1_000_000,
     q_buffer_size: 1_000_000,
 
+    replay: ReplayStorage::Memory,
+
+    exploitability: Some(10),
+
     profile: profile,
     eval: eval,
   }
@@ -223,6 +279,8 @@ pub fn plan_leduc2() -> Plan<ActionLimit, EvalNaive> {
       p_rate: HParam::new(0.05),
       q_rate: HParam::new(0.05),
       ramping: 0,
+      q_replay: QReplay::Uniform,
+      q_returns: QReturns::NStep(1),
     },
 
     snet_params: SNetParams { learning_rate: 0.1, batch_size: 128, accuracy: 128 },
@@ -240,6 +298,10 @@ pub fn plan_leduc2() -> Plan<ActionLimit, EvalNaive> {
     p_reservoir_size: 1_000_000,
     q_buffer_size: 1_000_000,
 
+    replay: ReplayStorage::Memory,
+
+    exploitability: Some(10),
+
     profile: profile,
     eval: eval,
   }
@@ -334,6 +396,8 @@ pub fn plan_texas_limit_n(n: usize, path_synthetic: &Path) -> Plan<ActionLimit,
       p_rate: HParam::new(0.05),
       q_rate: HParam::new(0.05),
       ramping: 0,
+      q_replay: QReplay::Uniform,
+      q_returns: QReturns::NStep(1),
     },
 
     snet_params: SNetParams { learning_rate: 0.001, batch_size: 1024, accuracy: 2048 },
@@ -351,6 +415,10 @@ pub fn plan_texas_limit_n(n: usize, path_synthetic: &Path) -> Plan<ActionLimit,
     p_reservoir_size: 6_000_000, // / n,
     q_buffer_size: 300_000,
 
+    replay: ReplayStorage::File,
+
+    exploitability: None,
+
     profile: profile,
     eval: eval,
   }
@@ -373,6 +441,14 @@ pub fn plan_texas_limit_zero_n(n: usize, path_synthetic: &Path) -> Plan<ActionLi
       q_updates_refit: 1000,
       batch_size: 256,
 
+      q_replay: QReplay::Prioritised {
+        priority: Priority::Proportional,
+        alpha: 0.6,
+        beta: HParam::linear(0.4, 1.0, 10_000_000),
+        epsilon: 0.01,
+      },
+      q_returns: QReturns::Lambda { lambda: 0.8, horizon: 8 },
+
       ..plan.agent_params
     },
 
@@ -119,28 +119,54 @@ impl<'a> QNet<'a> {
     Ok(tasks::Predict::new(nnet::NetworkContext::new(&self.q_graph_predict, self.hiddens.len())?))
   }
 
-  pub fn run_all_max(
+  /** Value of the best legal action of each state, selected by `q_select` and evaluated by
+   ** `q_eval` (double DQN). `masks` gives the legal actions per state, a state without any being
+   ** worth 0.
+   **/
+  pub fn run_all_best(
     &self,
-    q_target_predict: &mut tasks::Predict<f32, f32>,
+    q_select: &mut tasks::Train<f32, f32>,
+    q_eval: &mut tasks::Predict<f32, f32>,
     iss: &Vec<Vec<f32>>,
-  ) -> Result<Tensor<f32>, nnet::Error> {
+    masks: &[Vec<u8>],
+  ) -> Result<Vec<f32>, nnet::Error> {
     use nnet::tensor_of;
+    use std::cmp::Ordering;
+
     let mut is = Vec::new();
     for is_ in iss {
       is.extend(is_);
     }
 
     let is = tensor_of(&[iss.len() as u64, self.inputs as u64], &is)?;
-    let os = q_target_predict.run_get::<f32>(&is, "nnet_output_max")?;
-    Ok(os)
+    let os_select = q_select.predict(&is)?;
+    let os_eval = q_eval.run(&is)?;
+
+    let vs = masks
+      .iter()
+      .enumerate()
+      .map(|(i, mask)| {
+        let row = i * self.outputs;
+        mask
+          .iter()
+          .map(|&a| row + a as usize)
+          .max_by(|&a, &b| os_select[a].partial_cmp(&os_select[b]).unwrap_or(Ordering::Equal))
+          .map(|a| os_eval[a])
+          .unwrap_or(0.0)
+      })
+      .collect();
+
+    Ok(vs)
   }
 
+  // `weights` scales the loss of each sample, the graph taking them as `nnet_weights`.
   pub fn train_many(
     train: &mut tasks::Train<f32, f32>,
     times: usize,
     learning_rate: f32,
     inputs: &Tensor<f32>,
     outputs: &Tensor<f32>,
+    weights: Option<&Tensor<f32>>,
   ) -> Result<(f32, f32, f32), nnet::Error> {
     use std::cmp::Ordering;
 
@@ -148,11 +174,15 @@ impl<'a> QNet<'a> {
     let mut gradients_max = Vec::new();
     let mut gradients_min = Vec::new();
 
+    let feeds_tensors: Vec<(String, &Tensor<f32>)> =
+      weights.map(|w| vec![(String::from("nnet_weights"), w)]).unwrap_or(Vec::new());
+
     for _ in 0..times {
-      let results = train.run_(
+      let results = train.run_with(
         inputs,
         outputs,
         &[(String::from("nnet_learning_rate"), learning_rate)],
+        &feeds_tensors,
         &[
           String::from("nnet_cost"),
           String::from("nnet_gradients_max"),
@@ -248,6 +278,7 @@ impl QState {
         // Add action in history
         self.data_hist[player].push((self.round_id, self.lap, action));
       }
+      _ => {}
     }
   }
 
@@ -0,0 +1,600 @@
+use bincode::{deserialize, serialize, Infinite};
+use rand::Rng;
+use redis::{Client, Commands, Connection, RedisResult};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+  collections::{BTreeMap, HashSet},
+  fs::{self, File, OpenOptions},
+  io::{self, Read, Seek, SeekFrom, Write},
+  marker::PhantomData,
+  mem::replace,
+  path::{Path, PathBuf},
+};
+
+/** Storage of the replay memories of an agent, values being indexed from 0 to `len`.
+ **
+ ** A snapshot keeps the state of the buffer using the store (its cursor) along with the values,
+ ** a training being resumed from the last one.
+ **/
+pub trait ReplayStore<A> {
+  fn get(&mut self, k: usize) -> A;
+
+  // Replace the value at `k`, or append it when `k` is `len`.
+  fn set(&mut self, k: usize, value: A) -> ();
+
+  fn len(&mut self) -> usize;
+
+  fn snapshot_store(&mut self, state: &[usize]) -> ();
+
+  // State given to the last snapshot, the values being restored. `None` without any.
+  fn snapshot_load(&mut self) -> Option<Vec<usize>>;
+}
+
+impl<A, S: ReplayStore<A> + ?Sized> ReplayStore<A> for Box<S> {
+  fn get(&mut self, k: usize) -> A {
+    (**self).get(k)
+  }
+
+  fn set(&mut self, k: usize, value: A) -> () {
+    (**self).set(k, value)
+  }
+
+  fn len(&mut self) -> usize {
+    (**self).len()
+  }
+
+  fn snapshot_store(&mut self, state: &[usize]) -> () {
+    (**self).snapshot_store(state)
+  }
+
+  fn snapshot_load(&mut self) -> Option<Vec<usize>> {
+    (**self).snapshot_load()
+  }
+}
+
+/** Store in memory, lost with the process (snapshots are not supported).
+ **/
+pub struct MemoryStore<A> {
+  values: Vec<A>,
+}
+
+impl<A> MemoryStore<A> {
+  pub fn new() -> MemoryStore<A> {
+    MemoryStore { values: Vec::new() }
+  }
+
+  pub fn values(&self) -> &[A] {
+    &self.values
+  }
+}
+
+impl<A: Clone> ReplayStore<A> for MemoryStore<A> {
+  fn get(&mut self, k: usize) -> A {
+    self.values[k].clone()
+  }
+
+  fn set(&mut self, k: usize, value: A) -> () {
+    if k == self.values.len() {
+      self.values.push(value);
+    } else {
+      self.values[k] = value;
+    }
+  }
+
+  fn len(&mut self) -> usize {
+    self.values.len()
+  }
+
+  fn snapshot_store(&mut self, _: &[usize]) -> () {
+    warn!("Snapshot of a replay store in memory, values will be lost.");
+  }
+
+  fn snapshot_load(&mut self) -> Option<Vec<usize>> {
+    None
+  }
+}
+
+struct Segment {
+  file: File,
+  size: u64, // Bytes written
+  live: u64, // Bytes of the values indexed
+}
+
+/** Store of values appended to segment files, an index giving the place of each value.
+ **
+ ** Replacing a value appends it, a sealed segment being compacted once less than half of it is
+ ** live. A snapshot writes the index, the segments it refers to being kept until the next one, so
+ ** that values written since are ignored on load.
+ **/
+pub struct FileStore<A> {
+  path: PathBuf,
+  segment_size: u64,
+  segment: u32, // Segment appended to
+  segments: BTreeMap<u32, Segment>,
+  index: Vec<(u32, u64, u32)>, // (segment, offset, length) per value
+  pinned: HashSet<u32>,        // Segments of the last snapshot
+  dead: Vec<u32>,              // Pinned segments without live values
+  value: PhantomData<A>,
+}
+
+impl<A> FileStore<A> {
+  /** Open a store in `path`, segments being sealed past `segment_size` bytes.
+   **
+   ** Segments already in `path` are kept for `snapshot_load`, appends going to a new one.
+   **/
+  pub fn new(path: &Path, segment_size: u64) -> io::Result<FileStore<A>> {
+    fs::create_dir_all(path)?;
+
+    let mut segment = 0;
+    for entry in fs::read_dir(path)? {
+      if let Some(id) = FileStore::<A>::segment_id(&entry?.path()) {
+        segment = u32::max(segment, id + 1);
+      }
+    }
+
+    let mut store = FileStore {
+      path: path.to_path_buf(),
+      segment_size: segment_size,
+      segment: segment,
+      segments: BTreeMap::new(),
+      index: Vec::new(),
+      pinned: HashSet::new(),
+      dead: Vec::new(),
+      value: PhantomData,
+    };
+    store.segment_open(segment)?;
+    Ok(store)
+  }
+
+  fn segment_path(&self, id: u32) -> PathBuf {
+    self.path.join(format!("segment-{:08}.data", id))
+  }
+
+  fn segment_id(path: &Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    if name.starts_with("segment-") && name.ends_with(".data") {
+      name["segment-".len()..name.len() - ".data".len()].parse().ok()
+    } else {
+      None
+    }
+  }
+
+  fn segment_open(&mut self, id: u32) -> io::Result<()> {
+    let path = self.segment_path(id);
+    let file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+    let size = file.metadata()?.len();
+    self.segments.insert(id, Segment { file: file, size: size, live: 0 });
+    Ok(())
+  }
+
+  fn segment_remove(&mut self, id: u32) -> () {
+    self.segments.remove(&id);
+    if self.pinned.contains(&id) {
+      self.dead.push(id);
+    } else {
+      fs::remove_file(self.segment_path(id)).unwrap();
+    }
+  }
+
+  fn read(&mut self, place: (u32, u64, u32)) -> Vec<u8> {
+    let (id, offset, length) = place;
+    let ref mut file = self.segments.get_mut(&id).unwrap().file;
+
+    let mut xs = vec![0; length as usize];
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.read_exact(&mut xs).unwrap();
+    xs
+  }
+
+  fn append(&mut self, xs: &[u8]) -> (u32, u64, u32) {
+    let id = self.segment;
+    let segment = self.segments.get_mut(&id).unwrap();
+
+    let offset = segment.size;
+    segment.file.write_all(xs).unwrap();
+    segment.size += xs.len() as u64;
+    segment.live += xs.len() as u64;
+
+    (id, offset, xs.len() as u32)
+  }
+
+  // Place a value at `k`, releasing the bytes of the value replaced.
+  fn place(&mut self, k: usize, place: (u32, u64, u32)) -> () {
+    if k == self.index.len() {
+      self.index.push(place);
+    } else {
+      let (id, _, length) = replace(&mut self.index[k], place);
+
+      let live = {
+        let segment = self.segments.get_mut(&id).unwrap();
+        segment.live -= length as u64;
+        segment.live
+      };
+      if live == 0 && id != self.segment {
+        self.segment_remove(id);
+      }
+    }
+  }
+
+  // Append to a new segment, compacting the sealed ones mostly replaced.
+  fn seal(&mut self) -> () {
+    let id = self.segment + 1;
+    self.segment_open(id).unwrap();
+    self.segment = id;
+
+    let compacts: HashSet<u32> = self
+      .segments
+      .iter()
+      .filter(|&(&i, s)| i != id && s.live * 2 < s.size)
+      .map(|(&i, _)| i)
+      .collect();
+
+    if !compacts.is_empty() {
+      debug!("Compacting {} segments of {:?}.", compacts.len(), self.path);
+
+      for k in 0..self.index.len() {
+        let place = self.index[k];
+        if compacts.contains(&place.0) {
+          let xs = self.read(place);
+          let place_new = self.append(&xs);
+          self.place(k, place_new);
+        }
+      }
+    }
+  }
+}
+
+impl<A> ReplayStore<A> for FileStore<A>
+where
+  A: Serialize + DeserializeOwned,
+{
+  fn get(&mut self, k: usize) -> A {
+    let place = self.index[k];
+    let xs = self.read(place);
+    deserialize(&xs).unwrap()
+  }
+
+  fn set(&mut self, k: usize, value: A) -> () {
+    let xs = serialize(&value, Infinite).unwrap();
+    let place = self.append(&xs);
+    self.place(k, place);
+
+    if self.segments[&self.segment].size >= self.segment_size {
+      self.seal();
+    }
+  }
+
+  fn len(&mut self) -> usize {
+    self.index.len()
+  }
+
+  fn snapshot_store(&mut self, state: &[usize]) -> () {
+    self.segments[&self.segment].file.sync_data().unwrap();
+
+    let ref path = self.path.join("index.data");
+    let ref path_tmp = self.path.join("index.data.tmp");
+    {
+      let mut file = File::create(path_tmp).unwrap();
+      file.write_all(&serialize(&(state, &self.index), Infinite).unwrap()).unwrap();
+      file.sync_data().unwrap();
+    }
+    fs::rename(path_tmp, path).unwrap();
+
+    // Segments dead since the previous snapshot are no longer referred to.
+    for id in replace(&mut self.dead, Vec::new()) {
+      fs::remove_file(self.segment_path(id)).unwrap();
+    }
+    self.pinned = self.segments.keys().cloned().collect();
+  }
+
+  fn snapshot_load(&mut self) -> Option<Vec<usize>> {
+    let ref path = self.path.join("index.data");
+    if !path.exists() {
+      return None;
+    }
+
+    let mut xs = Vec::new();
+    File::open(path).unwrap().read_to_end(&mut xs).unwrap();
+    let (state, index): (Vec<usize>, Vec<(u32, u64, u32)>) = deserialize(&xs).unwrap();
+
+    self.segments.clear();
+    self.index = Vec::new();
+    self.dead = Vec::new();
+    self.pinned = index.iter().map(|&(id, _, _)| id).collect();
+
+    // Segments written after the snapshot are dropped.
+    let mut segment = 0;
+    for entry in fs::read_dir(&self.path).unwrap() {
+      let path = entry.unwrap().path();
+      if let Some(id) = FileStore::<A>::segment_id(&path) {
+        if self.pinned.contains(&id) {
+          segment = u32::max(segment, id + 1);
+        } else {
+          fs::remove_file(&path).unwrap();
+        }
+      }
+    }
+
+    let pinned: Vec<u32> = self.pinned.iter().cloned().collect();
+    for id in pinned {
+      self.segment_open(id).unwrap();
+    }
+    for &(id, _, length) in index.iter() {
+      self.segments.get_mut(&id).unwrap().live += length as u64;
+    }
+    self.index = index;
+
+    self.segment_open(segment).unwrap();
+    self.segment = segment;
+
+    Some(state)
+  }
+}
+
+/** Store in a Redis instance, values being prefixed by `namespace`.
+ **
+ ** A snapshot keeps the length along with the state, the values written since being kept on load
+ ** (memories of a same agent being interchangeable). It does not depend on when Redis saves.
+ ** Snapshots of the buffers prior to the stores (without length) are loaded too, values without
+ ** any snapshot failing the load rather than being overwritten from the start.
+ **/
+pub struct RedisStore<A> {
+  connection: Connection,
+  namespace: Vec<u8>,
+  value: PhantomData<A>,
+}
+
+impl<A> RedisStore<A> {
+  pub fn new(client: &Client, namespace: Vec<u8>) -> RedisResult<RedisStore<A>> {
+    let conn = client.get_connection()?;
+    Ok(RedisStore { connection: conn, namespace: namespace, value: PhantomData })
+  }
+
+  pub fn key(&self, k: usize) -> Vec<u8> {
+    let mut xs = self.namespace.clone();
+    xs.extend(serialize(&k, Infinite).unwrap());
+    xs
+  }
+
+  pub fn field_get<T>(&mut self, label: &str) -> Option<T>
+  where
+    T: DeserializeOwned,
+  {
+    let key = self.field_key(label);
+    let xs: Option<Vec<u8>> = self.connection.get(key).unwrap();
+    xs.map(|xs| deserialize(&xs).unwrap())
+  }
+
+  pub fn field_key(&self, label: &str) -> Vec<u8> {
+    let mut xs = self.key(0);
+    xs.extend(serialize(label, Infinite).unwrap());
+    xs
+  }
+
+  pub fn field_set<T>(&mut self, label: &str, v: T) -> ()
+  where
+    T: Serialize,
+  {
+    let key = self.field_key(label);
+    let xs: Vec<u8> = serialize(&v, Infinite).unwrap();
+    let res: String = self.connection.set(key, xs).unwrap();
+    assert!(res == "OK");
+    ()
+  }
+}
+
+impl<A> ReplayStore<A> for RedisStore<A>
+where
+  A: Serialize + DeserializeOwned,
+{
+  fn get(&mut self, k: usize) -> A {
+    let xs: Vec<u8> = self.connection.get(self.key(k)).unwrap();
+    deserialize(&xs).unwrap()
+  }
+
+  fn set(&mut self, k: usize, v: A) -> () {
+    let key = self.key(k);
+    let xs = serialize(&v, Infinite).unwrap();
+
+    let append = k == self.len();
+
+    let res: String = self.connection.set(key, xs).unwrap();
+    assert!(res == "OK");
+
+    if append {
+      let _: () = self.connection.incr(self.namespace.clone(), 1).unwrap();
+    }
+  }
+
+  fn len(&mut self) -> usize {
+    self.connection.get(self.namespace.clone()).unwrap_or(0)
+  }
+
+  fn snapshot_store(&mut self, state: &[usize]) -> () {
+    let len = self.len();
+    self.field_set("state", state);
+    self.field_set("len", len);
+  }
+
+  fn snapshot_load(&mut self) -> Option<Vec<usize>> {
+    // Buffers kept their cursor in the `i` and `laps` fields before the stores.
+    let state: Option<Vec<usize>> = match self.field_get("state") {
+      Some(state) => Some(state),
+      None => self.field_get::<usize>("i").map(|i| {
+        let mut state = vec![i];
+        state.extend(self.field_get::<usize>("laps"));
+        state
+      }),
+    };
+
+    let len = self.len();
+    if state.is_none() && len > 0 {
+      let namespace = String::from_utf8_lossy(&self.namespace);
+      panic!("Replay store {} of {} values without snapshot.", namespace, len);
+    }
+
+    if let Some(len) = self.field_get::<usize>("len") {
+      let _: () = self.connection.set(self.namespace.clone(), len).unwrap();
+    }
+    state
+  }
+}
+
+/** Circular Buffer
+ **/
+pub struct CBuffer<A, S> {
+  pub size: usize,
+  pub values: S,
+  i: usize,
+  laps: usize,
+  value: PhantomData<A>,
+}
+
+impl<A, S: ReplayStore<A>> CBuffer<A, S> {
+  pub fn new(size: usize, values: S) -> CBuffer<A, S> {
+    CBuffer { size: size, values: values, i: 0, laps: 0, value: PhantomData }
+  }
+
+  pub fn get(&mut self, k: usize) -> A {
+    self.values.get(k)
+  }
+
+  // Push values, returning the indices they were stored at.
+  pub fn push(&mut self, values: Vec<A>) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(values.len());
+
+    for value in values {
+      let size = self.values.len();
+      if size == self.size {
+        if self.i == self.size {
+          self.i = 0;
+          self.laps += 1;
+        }
+      }
+
+      self.values.set(self.i, value);
+      indices.push(self.i);
+
+      self.i += 1;
+    }
+
+    indices
+  }
+
+  pub fn usage(&mut self) -> f32 {
+    let size = self.values.len();
+    if size == self.size {
+      1.0
+    } else {
+      size as f32 / self.size as f32
+    }
+  }
+
+  pub fn len(&mut self) -> usize {
+    self.values.len()
+  }
+
+  pub fn snapshot_store(&mut self) -> () {
+    self.values.snapshot_store(&[self.i, self.laps]);
+  }
+
+  // Restore the last snapshot, `false` without any.
+  pub fn snapshot_load(&mut self) -> bool {
+    match self.values.snapshot_load() {
+      Some(state) => {
+        self.i = state[0];
+        self.laps = state[1];
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+impl<A> CBuffer<A, MemoryStore<A>> {
+  // Value pushed `age` values ago, the last one being 1.
+  pub fn get_age(&self, age: usize) -> &A {
+    let values = self.values.values();
+    &values[(self.i + values.len() - age) % values.len()]
+  }
+}
+
+/** Reservoir Sampling (incremental), Algorithm R
+ **  https://en.wikipedia.org/wiki/Reservoir_sampling
+ **/
+
+pub struct RSampler<A, S> {
+  pub size: usize,
+  pub values: S,
+  p_min: f32,
+  i: usize,
+  value: PhantomData<A>,
+}
+
+impl<A, S: ReplayStore<A>> RSampler<A, S> {
+  pub fn new(size: usize, p_min: f32, values: S) -> RSampler<A, S> {
+    RSampler { size: size, values: values, p_min: p_min, i: 0, value: PhantomData }
+  }
+
+  pub fn get(&mut self, k: usize) -> A {
+    self.values.get(k)
+  }
+
+  pub fn push<R: Rng>(&mut self, rng: &mut R, values: Vec<A>) -> usize {
+    use rand::distributions::{Distribution, Range};
+
+    let mut count = 0;
+
+    for value in values {
+      let size = self.values.len();
+      if size == self.size {
+        let p = (1.0 / self.i as f32) * (self.size as f32);
+        let p_norm = if p < self.p_min { self.p_min } else { p };
+
+        if rng.gen::<f32>() < p_norm {
+          count += 1;
+
+          let range = Range::new(0, self.size);
+          let j = range.sample(rng);
+
+          self.values.set(j, value);
+        }
+      } else {
+        count += 1;
+
+        self.values.set(size, value);
+      }
+      self.i += 1;
+    }
+
+    count
+  }
+
+  pub fn usage(&mut self) -> f32 {
+    let size = self.values.len();
+    if size == self.size {
+      1.0
+    } else {
+      size as f32 / self.size as f32
+    }
+  }
+
+  pub fn len(&mut self) -> usize {
+    self.values.len()
+  }
+
+  pub fn snapshot_store(&mut self) -> () {
+    self.values.snapshot_store(&[self.i]);
+  }
+
+  // Restore the last snapshot, `false` without any.
+  pub fn snapshot_load(&mut self) -> bool {
+    match self.values.snapshot_load() {
+      Some(state) => {
+        self.i = state[0];
+        true
+      }
+      None => false,
+    }
+  }
+}
@@ -36,38 +36,17 @@ impl<'a, R: Rng> SNetLex<'a, R> {
       table_cards: Vec::new(),
     }
   }
-}
 
-impl<'a, R: Rng> Players<()> for SNetLex<'a, R> {
-  fn init(
+  // Probabilities of fold, call and raise once the events are observed.
+  fn probs(
     &mut self,
-    blinds: &[Money],
-    player_first: SeatId,
-    player_funds: &[Money],
-    playing_hands: &Vec<(usize, Vec<Card>)>,
-  ) -> () {
-    self.blind_biggest = *blinds.iter().max().unwrap();
-    self.players = (0..self.profile.players).collect();
-
-    for &(seat_id, ref cards) in playing_hands {
-      self.players_cards.insert(seat_id, cards.clone());
-    }
-
-    self.table_cards = Vec::new();
-  }
-
-  fn play(
-    &mut self,
-    _: usize,
     table_target: Money,
     table_target_raise: Option<Money>,
     player_pots: &[Money],
     seat_id: usize,
-    _: Money,
     events: &[Event],
-  ) -> Result<u8, ()> {
+  ) -> Vec<f32> {
     use anna_eval::strength::return_rate;
-    use anna_utils::math;
 
     for event in events {
       match event {
@@ -91,7 +70,7 @@ impl<'a, R: Rng> Players<()> for SNetLex<'a, R> {
     let raise = table_target_raise.unwrap_or(Money::zero());
     let return_rate = return_rate(strength, player_pots, target + raise);
 
-    let probs = if return_rate < 0.8 {
+    let mut probs = if return_rate < 0.8 {
       vec![0.95, 0.00, 0.05]
     } else if return_rate < 1.0 {
       vec![0.80, 0.05, 0.15]
@@ -101,15 +80,68 @@ impl<'a, R: Rng> Players<()> for SNetLex<'a, R> {
       vec![0.00, 0.30, 0.70]
     };
 
-    let action_i = math::sample(&mut self.rng, probs);
+    // If fold and amount to call is zero, then call, as when raises are capped.
+    if target == Money::zero() {
+      probs[1] += probs[0];
+      probs[0] = 0.;
+    }
+    if table_target_raise.is_none() {
+      probs[1] += probs[2];
+      probs[2] = 0.;
+    }
 
-    // If fold and amount to call is zero, then call.
-    if action_i == 0 && target == Money::zero() {
-      Ok(1)
-    } else if action_i == 2 && table_target_raise.is_none() {
-      Ok(1)
-    } else {
-      Ok(action_i as u8)
+    probs
+  }
+}
+
+impl<'a, R: Rng> Players<()> for SNetLex<'a, R> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.blind_biggest = *blinds.iter().max().unwrap();
+    self.players = (0..self.profile.players).collect();
+
+    for &(seat_id, ref cards) in playing_hands {
+      self.players_cards.insert(seat_id, cards.clone());
     }
+
+    self.table_cards = Vec::new();
+  }
+
+  fn play(
+    &mut self,
+    _: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    _: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    use anna_utils::math;
+
+    let probs = self.probs(table_target, table_target_raise, player_pots, seat_id, events);
+    Ok(math::sample(&mut self.rng, probs) as u8)
+  }
+
+  fn policy(
+    &mut self,
+    _: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    _: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    let (players, table_cards) = (self.players.clone(), self.table_cards.clone());
+    let probs = self.probs(table_target, table_target_raise, player_pots, seat_id, events);
+    self.players = players;
+    self.table_cards = table_cards;
+    probs
   }
 }
@@ -0,0 +1,45 @@
+extern crate anna_learning;
+
+#[test]
+fn q_returns() {
+  use anna_learning::agent::QReturns;
+
+  let returns = QReturns::NStep(3);
+  assert!(returns.horizon() == 3);
+  assert!(returns.weights(3) == vec![0.0, 0.0, 1.0]);
+  assert!(returns.weights(1) == vec![1.0]);
+
+  let returns = QReturns::Lambda { lambda: 0.5, horizon: 4 };
+  assert!(returns.horizon() == 4);
+  assert!(returns.weights(4) == vec![0.5, 0.25, 0.125, 0.125]);
+  assert!(returns.weights(2) == vec![0.5, 0.5]);
+
+  // One-step and Monte Carlo returns
+  assert!(QReturns::Lambda { lambda: 0.0, horizon: 4 }.weights(4) == vec![1.0, 0.0, 0.0, 0.0]);
+  assert!(QReturns::Lambda { lambda: 1.0, horizon: 4 }.weights(4) == vec![0.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn q_return_watkins() {
+  use anna_learning::agent::{q_return, q_steps, QReturns};
+
+  let returns = QReturns::Lambda { lambda: 0.5, horizon: 4 };
+
+  // Cut at the next exploratory action, the own one of the transition not counting.
+  assert!(q_steps(&returns, &[true, false, false, false]) == 4);
+  assert!(q_steps(&returns, &[false, false, true, false]) == 2);
+  assert!(q_steps(&returns, &[false, true]) == 1);
+  // Past the horizon, or the end of the episode.
+  assert!(q_steps(&returns, &[false, false, false, false, true]) == 4);
+  assert!(q_steps(&returns, &[false, false]) == 2);
+  assert!(q_steps(&QReturns::NStep(3), &[false, false, true]) == 2);
+
+  // Bootstrapped on the state of the exploratory action, with the weight of the longer returns.
+  let weights = returns.weights(2);
+  let g = q_return(&weights, &[0.0, 0.0], &[Some(0.4), Some(0.8)], 0.5);
+  assert!(g == 0.5 * (0.5 * 0.4) + 0.5 * (0.25 * 0.8));
+
+  // Rewards discounted, terminal states worth 0.
+  let g = q_return(&[0.0, 1.0], &[1.0, 2.0], &[None, None], 0.5);
+  assert!(g == 1.0 + 0.5 * 2.0);
+}
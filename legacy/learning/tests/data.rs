@@ -0,0 +1,63 @@
+extern crate anna_learning;
+extern crate rand;
+
+#[test]
+fn sum_tree() {
+  use anna_learning::data::SumTree;
+
+  let mut tree = SumTree::new(5);
+  for (i, &p) in [1.0, 2.0, 0.0, 3.0, 4.0].iter().enumerate() {
+    tree.set(i, p);
+  }
+  assert!(tree.total() == 10.0);
+
+  assert!(tree.find(0.0) == 0);
+  assert!(tree.find(0.9) == 0);
+  assert!(tree.find(1.0) == 1);
+  assert!(tree.find(2.9) == 1);
+  assert!(tree.find(3.0) == 3);
+  assert!(tree.find(6.0) == 4);
+  assert!(tree.find(9.99) == 4);
+
+  tree.set(3, 0.5);
+  assert!(tree.total() == 7.5);
+  assert!(tree.get(3) == 0.5);
+  assert!(tree.find(3.4) == 3);
+  assert!(tree.find(3.5) == 4);
+}
+
+#[test]
+fn psampler() {
+  use anna_learning::data::{PSampler, Priority};
+  use rand::{SeedableRng, StdRng};
+
+  let ref mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  for &priority in [Priority::Proportional, Priority::Rank].iter() {
+    let mut sampler = PSampler::new(priority, 100, 1.0, 0.01);
+    for i in 0..100 {
+      sampler.push(i);
+    }
+
+    // Pushed values are sampled alike.
+    let (indices, weights) = sampler.sample(rng, 100, 1.0);
+    assert!(indices.len() == 100);
+    assert!(weights.iter().all(|&w| (w - 1.0).abs() < 1e-3));
+
+    // The value with the biggest error is the most sampled, with the lowest weight.
+    let errors: Vec<f32> = (0..100).map(|i| if i == 42 { 10.0 } else { 0.1 }).collect();
+    sampler.update(&(0..100).collect::<Vec<usize>>(), &errors);
+
+    let (indices, weights) = sampler.sample(rng, 1000, 1.0);
+    let hits = indices.iter().filter(|&&i| i == 42).count();
+    assert!(hits > 50);
+
+    let weight = weights[indices.iter().position(|&i| i == 42).unwrap()];
+    assert!(weights.iter().all(|&w| w >= weight && w <= 1.0));
+
+    // Pushed again, at the highest priority.
+    sampler.push(7);
+    let (indices, _) = sampler.sample(rng, 1000, 1.0);
+    assert!(indices.iter().filter(|&&i| i == 7).count() > 50);
+  }
+}
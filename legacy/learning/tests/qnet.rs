@@ -2,6 +2,7 @@
 extern crate arrayfire;
 extern crate env_logger;
 extern crate neutrinic;
+extern crate nnet;
 extern crate rand;
 
 extern crate anna_eval;
@@ -16,7 +17,7 @@ fn qstate_update() {
   use anna_eval::Eval;
   use anna_learning::qnet::{QNet, QState};
   use anna_model::{classifiers::ActionLimit, profile::profile_leduc};
-  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, Sim};
+  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, OddChip, Sim};
 
   use anna_model::Money;
 
@@ -26,7 +27,14 @@ fn qstate_update() {
 
   let blind = Money::new(1, 0);
 
-  let ref sim = Sim { action_class: action_class, blind_biggest: blind, profile: profile.clone() };
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
 
   // Synthetize games
   let seed: &[_] = &[0];
@@ -86,7 +94,7 @@ fn qstate_train() {
 
   use anna_eval::Eval;
   use anna_model::{classifiers::ActionLimit, profile::profile_leduc, Money};
-  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, Sim};
+  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, OddChip, Sim};
 
   use anna_learning::{behavior, qnet::QNet};
 
@@ -96,7 +104,14 @@ fn qstate_train() {
 
   let blind = Money::new(1, 0);
 
-  let ref sim = Sim { action_class: action_class, blind_biggest: blind, profile: profile.clone() };
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
 
   // Synthetize games
   let seed: &[_] = &[0];
@@ -145,3 +160,87 @@ fn qstate_train() {
 
   assert!(true == true);
 }
+
+#[test]
+fn qnet_run_all_best() {
+  use std::path::Path;
+
+  use nnet::{tensor_of, Network, TensorS};
+
+  use anna_learning::qnet::QNet;
+  use anna_model::{
+    cards::Card,
+    classifiers::ActionKuhn,
+    encoders::{ActionsEncoder, CardsEncoder},
+    profile::profile_kuhn,
+    Money,
+  };
+  use anna_simulation::{OddChip, Sim};
+
+  // Encoders of the sizes of the Kuhn graphs (7 inputs), states being all zeros.
+  struct Zeros(usize);
+
+  impl ActionsEncoder for Zeros {
+    fn encode(&self, _: &Vec<Vec<(usize, usize, u8)>>) -> Vec<f32> {
+      vec![0.0; self.0]
+    }
+    fn size(&self) -> usize {
+      self.0
+    }
+  }
+
+  impl CardsEncoder for Zeros {
+    fn encode(&mut self, _: &Vec<Vec<Card>>, _: usize) -> Vec<f32> {
+      vec![0.0; self.0]
+    }
+    fn size(&self) -> usize {
+      self.0
+    }
+  }
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: Money::new(1, 0),
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let graphs = Path::new("../resources/graphs");
+  let ref qnet = QNet::new(graphs, sim, &Zeros(6), &Zeros(1), &vec![16]).unwrap();
+
+  // Network of constant outputs `tanh(biases)`, the hidden layer being a single unit at 1.
+  let network = |biases: &[f32]| -> Network {
+    let mut hidden = vec![0.0; 16];
+    hidden[0] = 1.0;
+    let tensor =
+      |dims: &[u64], values: &[f32]| TensorS { tensor: tensor_of(dims, values).unwrap() };
+
+    Network {
+      biases: vec![tensor(&[16], &hidden), tensor(&[3], biases)],
+      weights: vec![tensor(&[7, 16], &[0.0; 7 * 16]), tensor(&[16, 3], &[0.0; 16 * 3])],
+    }
+  };
+
+  // The online network selects, the target network values.
+  let ref mut q_select = qnet.q_train().unwrap();
+  q_select.context.set(&network(&[0.1, 0.5, 0.9])).unwrap();
+  let ref mut q_eval = qnet.q_predict().unwrap();
+  q_eval.context.set(&network(&[0.3, -0.2, 0.7])).unwrap();
+
+  let iss = vec![vec![0.0; 7]; 4];
+  let masks = vec![vec![0, 1, 2], vec![0, 1], vec![1, 0], vec![]];
+  let vs = qnet.run_all_best(q_select, q_eval, &iss, &masks).unwrap();
+
+  let eq = |a: f32, b: f32| (a - b).abs() < 1e-5;
+  assert!(vs.len() == 4);
+  assert!(eq(vs[0], 0.7f32.tanh()));
+  // Raise, the best of both networks, is illegal: the online best call is valued by the target
+  // network, not its own best fold.
+  assert!(eq(vs[1], (-0.2f32).tanh()));
+  assert!(eq(vs[2], (-0.2f32).tanh()));
+  assert!(vs[3] == 0.0);
+}
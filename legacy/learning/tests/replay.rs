@@ -0,0 +1,72 @@
+extern crate anna_learning;
+extern crate rand;
+
+#[test]
+fn replay_memory() {
+  use anna_learning::replay::{CBuffer, MemoryStore, RSampler};
+  use rand::{SeedableRng, StdRng};
+
+  let mut buffer = CBuffer::new(3, MemoryStore::new());
+  buffer.push(vec![0, 1, 2, 3, 4]);
+
+  assert!(buffer.len() == 3);
+  assert!(buffer.usage() == 1.0);
+  assert!((0..3).map(|k| buffer.get(k)).collect::<Vec<usize>>() == vec![3, 4, 2]);
+  assert!((1..4).map(|age| *buffer.get_age(age)).collect::<Vec<usize>>() == vec![4, 3, 2]);
+
+  let ref mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let mut sampler = RSampler::new(10, 0.0, MemoryStore::new());
+  assert!(sampler.push(rng, (0..5).collect()) == 5);
+  assert!(sampler.usage() == 0.5);
+
+  sampler.push(rng, (5..1000).collect());
+  assert!(sampler.len() == 10);
+  assert!((0..10).all(|k| sampler.get(k) < 1000));
+}
+
+#[test]
+fn replay_file() {
+  use anna_learning::replay::{CBuffer, FileStore, RSampler, ReplayStore};
+  use rand::{SeedableRng, StdRng};
+  use std::{env::temp_dir, fs::remove_dir_all, process};
+
+  let ref path = temp_dir().join(format!("anna-replay-{}", process::id()));
+  let _ = remove_dir_all(path);
+
+  // Small segments, sealed and compacted along the laps.
+  let mut buffer = CBuffer::new(100, FileStore::new(&path.join("t"), 256).unwrap());
+  buffer.push((0..250).map(|x| (x, format!("{}", x))).collect());
+  buffer.snapshot_store();
+
+  let values: Vec<(usize, String)> = (0..100).map(|k| buffer.get(k)).collect();
+  assert!(values[0] == (200, "200".to_string()));
+  assert!(values[99] == (199, "199".to_string()));
+
+  // Values pushed after the snapshot are dropped on load.
+  buffer.push((250..300).map(|x| (x, format!("{}", x))).collect());
+
+  let mut buffer = CBuffer::new(100, FileStore::new(&path.join("t"), 256).unwrap());
+  assert!(buffer.len() == 0);
+  assert!(buffer.snapshot_load());
+  assert!((0..100).map(|k| buffer.get(k)).collect::<Vec<(usize, String)>>() == values);
+
+  buffer.push(vec![(250, "250".to_string())]);
+  assert!(buffer.get(50) == (250, "250".to_string()));
+  assert!(buffer.get(51) == (151, "151".to_string()));
+
+  // Without snapshot
+  let mut store: FileStore<usize> = FileStore::new(&path.join("b"), 256).unwrap();
+  assert!(store.snapshot_load().is_none());
+
+  let ref mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let mut sampler = RSampler::new(10, 0.0, store);
+  sampler.push(rng, (0..1000).collect());
+  sampler.snapshot_store();
+
+  let values: Vec<usize> = (0..10).map(|k| sampler.get(k)).collect();
+  let mut sampler = RSampler::new(10, 0.0, FileStore::new(&path.join("b"), 256).unwrap());
+  assert!(sampler.snapshot_load());
+  assert!((0..10).map(|k| sampler.get(k)).collect::<Vec<usize>>() == values);
+
+  remove_dir_all(path).unwrap();
+}
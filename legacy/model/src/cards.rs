@@ -67,6 +67,17 @@ impl<'de> Deserialize<'de> for Card {
           None => Err(E::custom(format!("u8 out of range for card: {}", value))),
         }
       }
+
+      // Self-describing formats (json) only provide u64.
+      fn visit_u64<E>(self, value: u64) -> Result<Card, E>
+      where
+        E: Error,
+      {
+        match value {
+          v if v <= u8::max_value() as u64 => self.visit_u8(v as u8),
+          _ => Err(E::custom(format!("u64 out of range for card: {}", value))),
+        }
+      }
     }
 
     d.deserialize_u8(CardVisitor)
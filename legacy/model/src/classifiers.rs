@@ -25,11 +25,12 @@ impl ActionClass for ActionKuhn {
     match action_class {
       0 => Ok((Action::Fold, None)),
       1 => Ok((Action::Call, if player_pot == table_target { None } else { Some(blind_biggest) })),
-      2 => Ok((
-        Action::Raise,
-        Some(Money::from_i32((table_target + blind_biggest) - player_pot).unwrap()),
-      )),
-      i => panic!(format!("Invalid action: {}", i)),
+      2 => {
+        let money = Money::from_i32((table_target + blind_biggest) - player_pot)
+          .ok_or(format!("Can not `Raise` above target. pot {}", player_pot))?;
+        Ok((Action::Raise, Some(money)))
+      }
+      i => Err(format!("Invalid action: {}", i)),
     }
   }
 
@@ -97,15 +98,20 @@ impl ActionClass for ActionLimit {
         let money = if player_pot == table_target {
           None
         } else {
-          Some(Money::from_i32(table_target - player_pot).unwrap())
+          Some(
+            Money::from_i32(table_target - player_pot)
+              .ok_or(format!("Can not `Call` above target. pot {}", player_pot))?,
+          )
         };
         Ok((Action::Call, money))
       }
       2 => {
         let raise = blind_biggest * (self.raises[round_id] as u32);
-        Ok((Action::Raise, Some(Money::from_i32((table_target + raise) - player_pot).unwrap())))
+        let money = Money::from_i32((table_target + raise) - player_pot)
+          .ok_or(format!("Can not `Raise` above target. pot {}", player_pot))?;
+        Ok((Action::Raise, Some(money)))
       }
-      i => panic!(format!("Invalid action: {}", i)),
+      i => Err(format!("Invalid action: {}", i)),
     }
   }
 
@@ -126,11 +132,13 @@ impl ActionClass for ActionLimit {
     player_pot: Money,
   ) -> HashSet<usize> {
     let raise = blind_biggest * (self.raises[round_id] as u32);
-    let raisable = table_target_raise.is_some() && player_fund >= raise;
+    // Going all-in is legal when it exactly matches the bet.
+    let raisable =
+      table_target_raise.is_some() && ((player_fund + player_pot) - (table_target + raise)) >= 0;
     let callable = if player_pot >= table_target {
       true
     } else {
-      ((player_fund + player_pot) - table_target) > 0
+      ((player_fund + player_pot) - table_target) >= 0
     };
 
     let mut xs = HashSet::new();
@@ -151,3 +159,190 @@ impl ActionClass for ActionLimit {
     3
   }
 }
+
+/** No-limit actions: fold, call, the raises and all-in.
+ **
+ ** Raises are given as ratio of the last raise of the round, the smallest being a full raise. A
+ ** call short of the target or an all-in below a full raise is played as an all-in `Call`.
+ **/
+#[derive(Clone)]
+pub struct ActionNoLimit {
+  pub raises: Vec<usize>, // As ratio of the last raise
+}
+
+impl ActionNoLimit {
+  fn all_in(&self) -> u8 {
+    2 + self.raises.len() as u8
+  }
+}
+
+impl ActionClass for ActionNoLimit {
+  fn apply(&self, _: Money, player_fund: Money, action: Action, pledge: Money) -> u8 {
+    match action {
+      Action::Raise if pledge == player_fund => self.all_in(),
+      action => action as u8,
+    }
+  }
+
+  fn unapply(
+    &self,
+    _: Money,
+    _: usize,
+    player_fund: Money,
+    table_target: Money,
+    table_target_raise: Money,
+    player_pot: Money,
+    action_class: u8,
+  ) -> Result<(Action, Option<Money>), String> {
+    use std::cmp::min;
+
+    match action_class {
+      0 => Ok((Action::Fold, None)),
+      1 => {
+        let money = if player_pot == table_target {
+          None
+        } else {
+          let call = Money::from_i32(table_target - player_pot)
+            .ok_or(format!("Can not `Call` above target. pot {}", player_pot))?;
+          Some(min(call, player_fund))
+        };
+        Ok((Action::Call, money))
+      }
+      i if i == self.all_in() => {
+        if ((player_fund + player_pot) - (table_target + table_target_raise)) >= 0 {
+          Ok((Action::Raise, Some(player_fund)))
+        } else {
+          Ok((Action::Call, Some(player_fund)))
+        }
+      }
+      i if i < self.all_in() => {
+        let raise = table_target_raise * (self.raises[i as usize - 2] as u32);
+        let money = Money::from_i32((table_target + raise) - player_pot)
+          .ok_or(format!("Can not `Raise` above target. pot {}", player_pot))?;
+        Ok((Action::Raise, Some(money)))
+      }
+      i => Err(format!("Invalid action: {}", i)),
+    }
+  }
+
+  fn is_fold(&self, class: u8) -> bool {
+    class == 0
+  }
+  fn is_raise(&self, class: u8) -> bool {
+    class >= 2
+  }
+
+  fn normalize(
+    &self,
+    _: Money,
+    _: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_fund: Money,
+    player_pot: Money,
+  ) -> HashSet<usize> {
+    let mut xs = HashSet::new();
+    if player_pot < table_target {
+      xs.insert(0);
+    }
+    xs.insert(1);
+
+    if let Some(table_target_raise) = table_target_raise {
+      let stack = player_fund + player_pot;
+      for (i, &ratio) in self.raises.iter().enumerate() {
+        if (stack - (table_target + table_target_raise * (ratio as u32))) >= 0 {
+          xs.insert(2 + i);
+        }
+      }
+      // Short of a full raise, all-in is a call.
+      if player_pot < table_target || (stack - (table_target + table_target_raise)) >= 0 {
+        xs.insert(self.all_in() as usize);
+      }
+    }
+
+    xs
+  }
+
+  fn size(&self) -> usize {
+    3 + self.raises.len()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use classifiers::*;
+  use Money;
+
+  fn limit_normalize(table_target: Money, player_fund: Money, player_pot: Money) -> Vec<usize> {
+    let action_class = ActionLimit { raises: vec![1, 1] };
+    let blind = Money::new(1, 0);
+
+    let xs = action_class.normalize(blind, 0, table_target, Some(blind), player_fund, player_pot);
+    let mut xs: Vec<usize> = xs.into_iter().collect();
+    xs.sort();
+    xs
+  }
+
+  #[test]
+  fn limit_normalize_call() -> () {
+    // Stack exactly matching the bet
+    assert!(limit_normalize(Money::new(2, 0), Money::new(1, 0), Money::new(1, 0)) == vec![0, 1]);
+    // Short of the bet
+    assert!(limit_normalize(Money::new(2, 0), Money::new(0, 99), Money::new(1, 0)) == vec![0]);
+    // Nothing to call
+    assert!(limit_normalize(Money::new(1, 0), Money::new(0, 0), Money::new(1, 0)) == vec![1]);
+  }
+
+  #[test]
+  fn limit_normalize_raise() -> () {
+    // Stack exactly matching the raise
+    assert!(limit_normalize(Money::new(2, 0), Money::new(2, 0), Money::new(1, 0)) == vec![0, 1, 2]);
+    // Covering the raise but not the call with it
+    assert!(limit_normalize(Money::new(2, 0), Money::new(1, 50), Money::new(0, 0)) == vec![0]);
+    assert!(limit_normalize(Money::new(2, 0), Money::new(1, 99), Money::new(1, 0)) == vec![0, 1]);
+  }
+
+  fn nolimit_normalize(table_target: Money, player_fund: Money, player_pot: Money) -> Vec<usize> {
+    let action_class = ActionNoLimit { raises: vec![1, 3] };
+    let blind = Money::new(1, 0);
+
+    let xs = action_class.normalize(blind, 0, table_target, Some(blind), player_fund, player_pot);
+    let mut xs: Vec<usize> = xs.into_iter().collect();
+    xs.sort();
+    xs
+  }
+
+  #[test]
+  fn nolimit_normalize_all_in() -> () {
+    // Deep stack
+    assert!(
+      nolimit_normalize(Money::new(2, 0), Money::new(9, 0), Money::new(1, 0))
+        == vec![0, 1, 2, 3, 4]
+    );
+    // Covering a full raise only
+    assert!(
+      nolimit_normalize(Money::new(2, 0), Money::new(2, 0), Money::new(1, 0)) == vec![0, 1, 2, 4]
+    );
+    // Short of the call, calling all-in
+    assert!(
+      nolimit_normalize(Money::new(2, 0), Money::new(0, 50), Money::new(1, 0)) == vec![0, 1, 4]
+    );
+    // At target, short of a full raise
+    assert!(nolimit_normalize(Money::new(1, 0), Money::new(0, 50), Money::new(1, 0)) == vec![1]);
+
+    let action_class = ActionNoLimit { raises: vec![1, 3] };
+    let blind = Money::new(1, 0);
+    let unapply =
+      |fund, target, pot, i| action_class.unapply(blind, 0, fund, target, blind, pot, i);
+    assert!(
+      unapply(Money::new(0, 50), blind * 2, blind, 4)
+        == Ok((Action::Call, Some(Money::new(0, 50))))
+    );
+    assert!(
+      unapply(Money::new(0, 50), blind * 2, blind, 1)
+        == Ok((Action::Call, Some(Money::new(0, 50))))
+    );
+    assert!(unapply(blind * 9, blind * 2, blind, 3) == Ok((Action::Raise, Some(blind * 4))));
+    assert!(action_class.apply(blind, blind * 9, Action::Raise, blind * 9) == 4);
+  }
+}
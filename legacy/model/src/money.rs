@@ -92,6 +92,17 @@ impl<'de> Deserialize<'de> for Money {
       {
         Ok(Money::from_u32(value))
       }
+
+      // Self-describing formats (json) only provide u64.
+      fn visit_u64<E>(self, value: u64) -> Result<Money, E>
+      where
+        E: Error,
+      {
+        match value {
+          v if v <= u32::max_value() as u64 => self.visit_u32(v as u32),
+          _ => Err(E::custom(format!("u64 out of range for money: {}", value))),
+        }
+      }
     }
 
     d.deserialize_u32(MoneyVisitor)
@@ -137,15 +137,18 @@ pub fn profile_texas_limit(players: usize, blind_small: Money, blind_big: Money)
   }
 }
 
-/*
 pub fn profile_texas_nolimit(players: usize, blind_small: Money, blind_big: Money) -> Profile {
+  use cards::{Card, CARDS};
+
+  let mut deck: Vec<Card> = CARDS.to_vec();
+  deck.sort();
+
   Profile {
-    players: players,
-    rounds: vec![2, 3, 1, 1]
+    id: "texas_nolimit".to_string(),
     blinds: vec![blind_small, blind_big],
-    deck:   CARDS,
-    limit:  None,
+    deck: deck,
+    rounds: vec![2, 3, 1, 1],
+    players: players,
+    limit: None,
   }
 }
-
-*/
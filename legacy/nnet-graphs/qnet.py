@@ -22,6 +22,8 @@ layers = ast.literal_eval(sys.argv[5])
 
 nnet_input = tf.placeholder(tf.float32, [None, inputs], name="nnet_input")
 nnet_target_logits = tf.placeholder(tf.float32, [None, outputs], name="nnet_target")
+# Importance-sampling weights of a prioritised replay, loss unweighted by default.
+nnet_weights = tf.placeholder_with_default(tf.ones_like(nnet_input[:, 0]), [None], name="nnet_weights")
 
 nnet_ws = []
 nnet_bs = []
@@ -62,7 +64,7 @@ def cross_entropy():
     return tf.losses.sparse_softmax_cross_entropy(labels=tf.argmax(nnet_target_logits, 1), logits=logits)
 
 def quadratic():
-    return tf.losses.mean_squared_error(labels=nnet_target_logits, predictions=nnet_output)
+    return tf.losses.mean_squared_error(labels=nnet_target_logits, predictions=nnet_output, weights=tf.expand_dims(nnet_weights, 1))
 
 if mode == tf.estimator.ModeKeys.PREDICT:
     nnet_output_max = tf.reduce_max(nnet_output, axis=1, name="nnet_output_max")
@@ -0,0 +1,25 @@
+# Reference client of the remote players protocol (see src/players_remote.rs), playing random legal
+# actions. Usage: `remote_client.py <host> <port>` or `remote_client.py <unix socket path>`.
+import json
+import random
+import socket
+import sys
+
+if len(sys.argv) == 3:
+    sock = socket.create_connection((sys.argv[1], int(sys.argv[2])))
+    sock.setsockopt(socket.IPPROTO_TCP, socket.TCP_NODELAY, 1)
+else:
+    sock = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    sock.connect(sys.argv[1])
+
+stream = sock.makefile('rw')
+
+for line in stream:
+    message = json.loads(line)
+
+    if message['type'] == 'observation':
+        action = random.choice(message['legal_actions'])
+        stream.write(json.dumps({'action': action}) + '\n')
+        stream.flush()
+    elif message['type'] == 'game_end':
+        print(message['player_funds'], message['showdown'], file=sys.stderr)
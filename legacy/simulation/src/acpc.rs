@@ -0,0 +1,796 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use anna_model::{
+  cards::{Card, CARD_VALS, SUITS},
+  Action, ActionClass, Money,
+};
+use engine::Score;
+use players::Players;
+use Act;
+use Event;
+use SeatId;
+use Sim;
+
+// Annual Computer Poker Competition protocol, limit games only.
+// ref: http://www.computerpokercompetition.org/downloads/documents/protocols/protocol.pdf
+
+const RANKS: &str = "23456789TJQKA";
+const SUITS_ACPC: &str = "shdc"; // Following `cards::SUITS`.
+
+pub fn card_format(card: &Card) -> String {
+  let rank = RANKS.as_bytes()[card.value as usize] as char;
+  let suit = SUITS_ACPC.as_bytes()[card.suit as usize] as char;
+  format!("{}{}", rank, suit)
+}
+
+pub fn card_parse(s: &str) -> Option<Card> {
+  let mut chars = s.chars();
+  let rank = chars.next().and_then(|c| RANKS.find(c))?;
+  let suit = chars.next().and_then(|c| SUITS_ACPC.find(c))?;
+  if chars.next().is_some() {
+    return None;
+  }
+  Some(Card { value: CARD_VALS[rank], suit: SUITS[suit] })
+}
+
+fn cards_format(cards: &[Card]) -> String {
+  cards.iter().map(card_format).collect()
+}
+
+fn cards_parse(s: &str) -> Result<Vec<Card>, String> {
+  if s.len() % 2 != 0 || !s.is_ascii() {
+    return Err(format!("Invalid cards: {}", s));
+  }
+  (0..s.len() / 2)
+    .map(|i| card_parse(&s[i * 2..i * 2 + 2]).ok_or(format!("Invalid cards: {}", s)))
+    .collect()
+}
+
+/** Game definition, as read by ACPC dealers and clients.
+ **
+ ** Positions are relative to the hand, chips are cents.
+ **/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Game {
+  pub players: usize,
+  pub blinds: Vec<Money>,       // By position.
+  pub raise_sizes: Vec<Money>,  // By round.
+  pub first_player: Vec<usize>, // By round, first position to act.
+  pub max_raises: Vec<usize>,   // By round.
+  pub suits: usize,
+  pub ranks: usize,
+  pub hole_cards: usize,
+  pub board_cards: Vec<usize>, // By round.
+}
+
+impl Game {
+  // Definition of a limit profile following the engine rules, the first player of a game being
+  // at position 0.
+  pub fn from_sim(sim: &Sim) -> Game {
+    use std::collections::HashSet;
+
+    let profile = &sim.profile;
+    let limit = profile.limit.clone().expect("ACPC games require a limit profile.");
+
+    let mut blinds = profile.blinds.clone();
+    blinds.resize(profile.players, Money::zero());
+    blinds.truncate(profile.players);
+
+    let rounds = profile.rounds.len();
+    let blinds_size = profile.blinds.len().min(profile.players);
+
+    let mut first_player = vec![0; rounds];
+    first_player[0] = blinds_size % profile.players;
+
+    let mut board_cards = profile.rounds.clone();
+    board_cards[0] = 0;
+
+    Game {
+      players: profile.players,
+      blinds: blinds,
+      raise_sizes: limit.raises.iter().map(|&r| sim.blind_biggest * r as u32).collect(),
+      first_player: first_player,
+      max_raises: vec![limit.caps; rounds],
+      suits: profile.deck.iter().map(|c| c.suit).collect::<HashSet<_>>().len(),
+      ranks: profile.deck.iter().map(|c| c.value).collect::<HashSet<_>>().len(),
+      hole_cards: profile.rounds[0],
+      board_cards: board_cards,
+    }
+  }
+
+  pub fn parse(s: &str) -> Result<Game, String> {
+    let mut players = None;
+    let mut rounds = None;
+    let mut blinds = Vec::new();
+    let mut raise_sizes = Vec::new();
+    let mut first_player = Vec::new();
+    let mut max_raises = Vec::new();
+    let mut suits = 4;
+    let mut ranks = 13;
+    let mut hole_cards = 0;
+    let mut board_cards = Vec::new();
+
+    fn numbers(value: &str) -> Result<Vec<u32>, String> {
+      value
+        .split_whitespace()
+        .map(|x| x.parse::<u32>().map_err(|_| format!("Invalid number: {}", x)))
+        .collect()
+    }
+
+    for line in s.lines().map(|l| l.trim()) {
+      let lower = line.to_lowercase();
+      if line.is_empty() || line.starts_with('#') || lower == "gamedef" || lower == "end gamedef" {
+        continue;
+      }
+      if lower == "nolimit" {
+        return Err("No-limit games are not supported.".to_string());
+      }
+      if lower == "limit" {
+        continue;
+      }
+
+      let mut parts = line.splitn(2, '=');
+      let key = parts.next().unwrap().trim().to_lowercase();
+      let value = parts.next().ok_or(format!("Invalid line: {}", line))?;
+      let xs = numbers(value)?;
+      let first = *xs.first().ok_or(format!("Missing value: {}", line))? as usize;
+
+      match key.as_str() {
+        "numplayers" => players = Some(first),
+        "numrounds" => rounds = Some(first),
+        "blind" => blinds = xs.iter().map(|&x| Money::from_u32(x)).collect(),
+        "raisesize" => raise_sizes = xs.iter().map(|&x| Money::from_u32(x)).collect(),
+        "firstplayer" => {
+          first_player = xs.iter().map(|&x| (x as usize).saturating_sub(1)).collect()
+        }
+        "maxraises" => max_raises = xs.iter().map(|&x| x as usize).collect(),
+        "numsuits" => suits = first,
+        "numranks" => ranks = first,
+        "numholecards" => hole_cards = first,
+        "numboardcards" => board_cards = xs.iter().map(|&x| x as usize).collect(),
+        "stack" => {} // Irrelevant to limit games.
+        _ => return Err(format!("Unknown key: {}", key)),
+      }
+    }
+
+    let players = players.ok_or("Missing numPlayers.")?;
+    let rounds = rounds.ok_or("Missing numRounds.")?;
+
+    if blinds.len() != players {
+      return Err("Expected a blind by player.".to_string());
+    }
+    if raise_sizes.len() != rounds || first_player.len() != rounds || board_cards.len() != rounds {
+      return Err("Expected raiseSize, firstPlayer and numBoardCards by round.".to_string());
+    }
+    if max_raises.is_empty() {
+      max_raises = vec![usize::max_value(); rounds];
+    }
+
+    Ok(Game {
+      players: players,
+      blinds: blinds,
+      raise_sizes: raise_sizes,
+      first_player: first_player,
+      max_raises: max_raises,
+      suits: suits,
+      ranks: ranks,
+      hole_cards: hole_cards,
+      board_cards: board_cards,
+    })
+  }
+
+  // Replay the betting of a hand, rounds separated.
+  pub fn betting(&self, rounds: &[Vec<Action>]) -> Result<Betting, String> {
+    let mut betting = Betting {
+      round_id: 0,
+      pots: self.blinds.clone(),
+      folded: vec![false; self.players],
+      raises: 0,
+      acting: None,
+      acts: Vec::new(),
+    };
+
+    let mut target = *self.blinds.iter().max().unwrap();
+    let mut round_id = 0;
+
+    loop {
+      betting.round_id = round_id;
+      betting.raises = 0;
+      betting.acts.push(Vec::new());
+
+      let mut acting = betting.next_active(self.first_player[round_id]);
+      let mut pending = betting.actives();
+
+      for &action in rounds.get(round_id).map(|xs| xs.as_slice()).unwrap_or(&[]) {
+        if pending == 0 || betting.actives() < 2 {
+          return Err(format!("Unexpected action in round {}.", round_id));
+        }
+
+        let pledge = match action {
+          Action::Fold => {
+            betting.folded[acting] = true;
+            pending -= 1;
+            Money::zero()
+          }
+          Action::Call => {
+            let pledge = Money::from_i32(target - betting.pots[acting]).unwrap_or(Money::zero());
+            betting.pots[acting] = target;
+            pending -= 1;
+            pledge
+          }
+          Action::Raise => {
+            if betting.raises >= self.max_raises[round_id] {
+              return Err(format!("Too many raises in round {}.", round_id));
+            }
+            target = target + self.raise_sizes[round_id];
+            let pledge = Money::from_i32(target - betting.pots[acting]).unwrap();
+            betting.pots[acting] = target;
+            betting.raises += 1;
+            pending = betting.actives() - 1;
+            pledge
+          }
+        };
+
+        betting.acts[round_id].push((acting, action, pledge));
+        acting = betting.next_active(acting + 1);
+      }
+
+      if betting.actives() < 2 {
+        return Ok(betting);
+      }
+
+      if pending > 0 {
+        betting.acting = Some(acting);
+        return Ok(betting);
+      }
+
+      if round_id + 1 == self.first_player.len() {
+        return Ok(betting); // Showdown.
+      }
+
+      round_id += 1;
+    }
+  }
+}
+
+impl fmt::Display for Game {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let join = |xs: Vec<String>| xs.join(" ");
+
+    writeln!(f, "GAMEDEF")?;
+    writeln!(f, "limit")?;
+    writeln!(f, "numPlayers = {}", self.players)?;
+    writeln!(f, "numRounds = {}", self.first_player.len())?;
+    writeln!(f, "blind = {}", join(self.blinds.iter().map(|m| m.unpack().to_string()).collect()))?;
+    writeln!(
+      f,
+      "raiseSize = {}",
+      join(self.raise_sizes.iter().map(|m| m.unpack().to_string()).collect())
+    )?;
+    writeln!(
+      f,
+      "firstPlayer = {}",
+      join(self.first_player.iter().map(|p| (p + 1).to_string()).collect())
+    )?;
+    writeln!(f, "maxRaises = {}", join(self.max_raises.iter().map(|x| x.to_string()).collect()))?;
+    writeln!(f, "numSuits = {}", self.suits)?;
+    writeln!(f, "numRanks = {}", self.ranks)?;
+    writeln!(f, "numHoleCards = {}", self.hole_cards)?;
+    writeln!(
+      f,
+      "numBoardCards = {}",
+      join(self.board_cards.iter().map(|x| x.to_string()).collect())
+    )?;
+    writeln!(f, "END GAMEDEF")
+  }
+}
+
+// State of the betting of a hand.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Betting {
+  pub round_id: usize,
+  pub pots: Vec<Money>, // By position.
+  pub folded: Vec<bool>,
+  pub raises: usize,         // Count of raises in the current round.
+  pub acting: Option<usize>, // None once the hand is over.
+  pub acts: Vec<Vec<(usize, Action, Money)>>, // Position, action and pledge by round.
+}
+
+impl Betting {
+  fn actives(&self) -> usize {
+    self.folded.iter().filter(|&&f| !f).count()
+  }
+
+  fn next_active(&self, position: usize) -> usize {
+    let n = self.folded.len();
+    (0..n).map(|i| (position + i) % n).find(|&p| !self.folded[p]).unwrap_or(position % n)
+  }
+
+  pub fn target(&self) -> Money {
+    *self.pots.iter().max().unwrap()
+  }
+}
+
+/** State of a match as seen by a player
+ **
+ **   MATCHSTATE:<position>:<hand>:<betting>:<hole cards by position>/<board cards by round>
+ **/
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchState {
+  pub position: usize,
+  pub hand: usize,
+  pub betting: Vec<Vec<Action>>,   // By round.
+  pub hole_cards: Vec<Vec<Card>>,  // By position, empty when hidden.
+  pub board_cards: Vec<Vec<Card>>, // By round, starting with the flop.
+}
+
+impl MatchState {
+  pub fn parse(s: &str) -> Result<MatchState, String> {
+    let parts: Vec<&str> = s.trim_end().splitn(5, ':').collect();
+    if parts.len() != 5 || parts[0] != "MATCHSTATE" {
+      return Err(format!("Invalid match state: {}", s));
+    }
+
+    let position = parts[1].parse().map_err(|_| format!("Invalid position: {}", parts[1]))?;
+    let hand = parts[2].parse().map_err(|_| format!("Invalid hand: {}", parts[2]))?;
+
+    let mut betting = Vec::new();
+    for round in parts[3].split('/') {
+      let mut actions = Vec::new();
+      for c in round.chars() {
+        actions.push(match c {
+          'f' => Action::Fold,
+          'c' | 'k' => Action::Call,
+          'r' | 'b' => Action::Raise,
+          _ => return Err(format!("Invalid betting: {}", parts[3])),
+        });
+      }
+      betting.push(actions);
+    }
+
+    // Replies of clients are appended after the cards.
+    let cards = parts[4].split(':').next().unwrap();
+    let mut rounds = cards.split('/');
+    let hole_cards: Vec<Vec<Card>> =
+      rounds.next().unwrap().split('|').map(cards_parse).collect::<Result<_, _>>()?;
+    let board_cards: Vec<Vec<Card>> = rounds.map(cards_parse).collect::<Result<_, _>>()?;
+
+    Ok(MatchState {
+      position: position,
+      hand: hand,
+      betting: betting,
+      hole_cards: hole_cards,
+      board_cards: board_cards,
+    })
+  }
+}
+
+impl fmt::Display for MatchState {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let betting: Vec<String> = self
+      .betting
+      .iter()
+      .map(|actions| {
+        actions
+          .iter()
+          .map(|a| match a {
+            &Action::Fold => 'f',
+            &Action::Call => 'c',
+            &Action::Raise => 'r',
+          })
+          .collect()
+      })
+      .collect();
+
+    let hole_cards: Vec<String> = self.hole_cards.iter().map(|cs| cards_format(cs)).collect();
+
+    write!(
+      f,
+      "MATCHSTATE:{}:{}:{}:{}",
+      self.position,
+      self.hand,
+      betting.join("/"),
+      hole_cards.join("|")
+    )?;
+    for cards in self.board_cards.iter() {
+      write!(f, "/{}", cards_format(cards))?;
+    }
+    Ok(())
+  }
+}
+
+fn action_char(action_class: &ActionClass, action_i: u8) -> char {
+  if action_class.is_fold(action_i) {
+    'f'
+  } else if action_class.is_raise(action_i) {
+    'r'
+  } else {
+    'c'
+  }
+}
+
+fn action_from_class(action_class: &ActionClass, action_i: u8) -> Action {
+  if action_class.is_fold(action_i) {
+    Action::Fold
+  } else if action_class.is_raise(action_i) {
+    Action::Raise
+  } else {
+    Action::Call
+  }
+}
+
+/** Client
+ **
+ ** Play a match on an ACPC dealer, seats being the positions of the game with the first player at
+ ** position 0. Returns the count of hands played once the dealer closes the connection.
+ **/
+pub fn client_run<S: Read + Write, E, P: Players<E>>(
+  stream: S,
+  game: &Game,
+  action_class: &ActionClass,
+  fund: Money,
+  players: &mut P,
+) -> io::Result<usize> {
+  let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+
+  let mut stream = BufReader::new(stream);
+  stream.get_mut().write_all(b"VERSION:2.0.0\r\n")?;
+  stream.get_mut().flush()?;
+
+  let blind_biggest = *game.blinds.iter().max().unwrap();
+  let blinds_ante = game.blinds.iter().all(|&m| m == blind_biggest);
+
+  let mut hand = None;
+  let mut hands = 0;
+  let mut acted = false;
+  let mut line = String::new();
+
+  loop {
+    line.clear();
+    if stream.read_line(&mut line)? == 0 {
+      return Ok(hands);
+    }
+
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+      continue;
+    }
+
+    let state = MatchState::parse(line).map_err(&invalid)?;
+    let position = state.position;
+
+    if hand != Some(state.hand) {
+      hand = Some(state.hand);
+      hands += 1;
+      acted = false;
+
+      let cards = state.hole_cards.get(position).cloned().unwrap_or(Vec::new());
+      players.init(&game.blinds, 0, &vec![fund; game.players], &vec![(position, cards)]);
+    }
+
+    let betting = game.betting(&state.betting).map_err(&invalid)?;
+    if betting.acting != Some(position) {
+      continue;
+    }
+
+    // Events as produced by the engine.
+    let mut events = Vec::new();
+    if !blinds_ante {
+      for (p, &blind) in game.blinds.iter().enumerate().filter(|&(_, &m)| m > Money::zero()) {
+        let action_i = action_class.apply(blind_biggest, fund, Action::Raise, blind);
+        events.push(Event::Play(Act { seat_id: p, action: action_i }));
+      }
+    }
+    for (round_id, acts) in betting.acts.iter().enumerate() {
+      if round_id > 0 {
+        let cards = state.board_cards.get(round_id - 1).cloned().unwrap_or(Vec::new());
+        events.push(Event::Table { cards: cards });
+      }
+      for &(p, action, pledge) in acts {
+        let player_fund = Money::from_i32(fund - betting.pots[p]).unwrap_or(Money::zero());
+        let action_i = action_class.apply(blind_biggest, player_fund, action, pledge);
+        events.push(Event::Play(Act { seat_id: p, action: action_i }));
+      }
+    }
+
+    // Events start with the last action of the player, once it played.
+    let events_begin = if acted {
+      events
+        .iter()
+        .rposition(|e| match e {
+          &Event::Play(Act { seat_id, .. }) => seat_id == position,
+          _ => false,
+        })
+        .unwrap_or(0)
+    } else {
+      0
+    };
+    acted = true;
+
+    let round_id = betting.round_id;
+    let table_target_raise = if betting.raises < game.max_raises[round_id] {
+      Some(game.raise_sizes[round_id])
+    } else {
+      None
+    };
+
+    let action_i = players
+      .play(
+        round_id,
+        betting.target(),
+        table_target_raise,
+        &betting.pots,
+        position,
+        Money::from_i32(fund - betting.pots[position]).unwrap_or(Money::zero()),
+        &events[events_begin..],
+      )
+      .map_err(|_| io::Error::new(io::ErrorKind::Other, "Player failed to play."))?;
+
+    let reply = format!("{}:{}\r\n", line, action_char(action_class, action_i));
+    stream.get_mut().write_all(reply.as_bytes())?;
+    stream.get_mut().flush()?;
+  }
+}
+
+/** Dealer side
+ **
+ ** Players of a table reached through ACPC clients, one by seat. As ACPC dealers do, the match
+ ** state is sent to every client after each action, the seat to act answering it, and the hand
+ ** closes with a final state showing the hands of the showdown. Table cards the engine does not
+ ** deal, the game ending with players all-in, are not shown. Invalid actions are played as calls.
+ **/
+pub struct PlayersAcpc<'a, S: Read + Write> {
+  action_class: &'a ActionClass,
+  streams: Vec<BufReader<S>>, // By seat.
+  hand: usize,
+  blind_biggest: Money,
+  blinds_skip: usize, // Blinds events to skip from the betting.
+  players: usize,
+  player_first: SeatId,
+  players_cards: Vec<(SeatId, Vec<Card>)>,
+  players_events: Vec<(SeatId, Vec<Event>)>,
+  events_sent: Option<usize>, // Events of the last state sent.
+  act_last: Option<Act>,      // Closes the game when followed by `game_end`.
+  pub error: Option<io::Error>,
+}
+
+impl<'a> PlayersAcpc<'a, TcpStream> {
+  // Wait for a client by seat to connect and announce its protocol version, seats being taken in
+  // the order of the connections.
+  pub fn tcp_accept(
+    listener: &TcpListener,
+    action_class: &'a ActionClass,
+    players: usize,
+  ) -> io::Result<PlayersAcpc<'a, TcpStream>> {
+    let mut streams = Vec::new();
+    for _ in 0..players {
+      let (stream, _) = listener.accept()?;
+      stream.set_nodelay(true)?;
+      streams.push(stream);
+    }
+    PlayersAcpc::new(streams, action_class)
+  }
+}
+
+impl<'a, S: Read + Write> PlayersAcpc<'a, S> {
+  pub fn new(streams: Vec<S>, action_class: &'a ActionClass) -> io::Result<PlayersAcpc<'a, S>> {
+    let mut readers = Vec::new();
+    for stream in streams {
+      let mut stream = BufReader::new(stream);
+
+      let mut version = String::new();
+      stream.read_line(&mut version)?;
+      if !version.starts_with("VERSION:2.") {
+        return Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("Unsupported protocol: {}", version.trim_end()),
+        ));
+      }
+      readers.push(stream);
+    }
+
+    Ok(PlayersAcpc {
+      action_class: action_class,
+      streams: readers,
+      hand: 0,
+      blind_biggest: Money::zero(),
+      blinds_skip: 0,
+      players: 0,
+      player_first: 0,
+      players_cards: Vec::new(),
+      players_events: Vec::new(),
+      events_sent: None,
+      act_last: None,
+      error: None,
+    })
+  }
+
+  // State seen by a seat, its own hole cards being shown along with the ones of the showdown.
+  fn match_state(
+    &self,
+    seat_id: SeatId,
+    events: &[Event],
+    showdown: &[(SeatId, Vec<Card>)],
+  ) -> MatchState {
+    let position = |s: SeatId| (s + self.players - self.player_first) % self.players;
+
+    let mut hole_cards = vec![Vec::new(); self.players];
+    let shown = self.players_cards.iter().filter(|&&(s, _)| s == seat_id).chain(showdown.iter());
+    for &(s, ref cards) in shown {
+      hole_cards[position(s)] = cards.clone();
+    }
+
+    let mut betting = vec![Vec::new()];
+    let mut board_cards = Vec::new();
+    for event in events.iter().skip(self.blinds_skip) {
+      match event {
+        &Event::Play(Act { action, .. }) => {
+          betting.last_mut().unwrap().push(action_from_class(self.action_class, action))
+        }
+        &Event::Table { ref cards } => {
+          betting.push(Vec::new());
+          board_cards.push(cards.clone());
+        }
+        _ => {}
+      }
+    }
+
+    MatchState {
+      position: position(seat_id),
+      hand: self.hand - 1,
+      betting: betting,
+      hole_cards: hole_cards,
+      board_cards: board_cards,
+    }
+  }
+
+  // Send the state of the game to every seat.
+  fn broadcast(&mut self, events: &[Event], showdown: &[(SeatId, Vec<Card>)]) -> io::Result<()> {
+    for seat_id in 0..self.streams.len() {
+      let state = self.match_state(seat_id, events, showdown);
+      let stream = self.streams[seat_id].get_mut();
+      stream.write_all(format!("{}\r\n", state).as_bytes())?;
+      stream.flush()?;
+    }
+    Ok(())
+  }
+
+  fn error_keep<A>(&mut self, result: io::Result<A>) -> Option<A> {
+    match result {
+      Ok(a) => Some(a),
+      Err(e) => {
+        error!("ACPC client: {}", e);
+        self.error = Some(e);
+        None
+      }
+    }
+  }
+}
+
+impl<'a, S: Read + Write> Players<()> for PlayersAcpc<'a, S> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.blind_biggest = *blinds.iter().max().unwrap();
+    self.players = player_funds.len();
+    self.player_first = player_first;
+    self.blinds_skip = if blinds.iter().all(|&m| m == self.blind_biggest) {
+      0
+    } else {
+      blinds.len().min(self.players)
+    };
+    self.players_cards = playing_hands.clone();
+    self.players_events = Vec::new();
+    self.events_sent = None;
+    self.act_last = None;
+    self.hand += 1;
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    // Events start with the last action of the player after its first play, the events of the
+    // seat being then the whole game.
+    let i = match self.players_events.iter().position(|&(s, _)| s == seat_id) {
+      Some(i) => i,
+      None => {
+        self.players_events.push((seat_id, Vec::new()));
+        self.players_events.len() - 1
+      }
+    };
+    self.players_events[i].1.extend(events.iter().cloned());
+    let events = self.players_events[i].1.clone();
+
+    // States of the decisions since the last one sent, closing with the decision of the seat.
+    let begin = self.events_sent.map_or(self.blinds_skip, |n| n + 1);
+    let decisions: Vec<usize> = (begin..events.len())
+      .filter(|&j| match events[j] {
+        Event::Play(_) => true,
+        _ => false,
+      })
+      .chain(Some(events.len()))
+      .collect();
+    self.events_sent = Some(events.len());
+
+    let mut result = Ok(());
+    for &j in decisions.iter() {
+      result = result.and_then(|_| self.broadcast(&events[..j], &[]));
+    }
+    self.error_keep(result).ok_or(())?;
+
+    let state = self.match_state(seat_id, &events, &[]).to_string();
+
+    let mask = self.action_class.normalize(
+      self.blind_biggest,
+      round_id,
+      table_target,
+      table_target_raise,
+      player_fund,
+      player_pots[seat_id],
+    );
+
+    let result = {
+      let stream = &mut self.streams[seat_id];
+      let mut reply = String::new();
+      stream.read_line(&mut reply).and_then(|n| match reply.trim_end() {
+        _ if n == 0 => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed.")),
+        r if r.starts_with(&state) && r.len() > state.len() + 1 => {
+          Ok(r[state.len() + 1..].to_string())
+        }
+        r => Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid reply: {}", r))),
+      })
+    };
+
+    let action = self.error_keep(result).ok_or(())?;
+
+    let action_i = match action.chars().next() {
+      Some('f') => {
+        self.action_class.apply(self.blind_biggest, player_fund, Action::Fold, Money::zero())
+      }
+      Some('r') | Some('b') => {
+        self.action_class.apply(self.blind_biggest, player_fund, Action::Raise, Money::zero())
+      }
+      _ => self.action_class.apply(self.blind_biggest, player_fund, Action::Call, Money::zero()),
+    };
+
+    let action_i = if mask.contains(&(action_i as usize)) {
+      action_i
+    } else {
+      warn!("ACPC client: invalid action {}, played as call.", action);
+      self.action_class.apply(self.blind_biggest, player_fund, Action::Call, Money::zero())
+    };
+
+    self.act_last = Some(Act { seat_id: seat_id, action: action_i });
+    Ok(action_i)
+  }
+
+  // Final state, the last action played closing the game.
+  fn game_end(&mut self, _: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    let act = match self.act_last.take() {
+      Some(act) => act,
+      None => return,
+    };
+
+    let mut events = match self.players_events.iter().find(|&&(s, _)| s == act.seat_id) {
+      Some(&(_, ref events)) => events.clone(),
+      None => return,
+    };
+    events.push(Event::Play(act));
+
+    let result = self.broadcast(&events, showdown);
+    self.error_keep(result);
+  }
+}
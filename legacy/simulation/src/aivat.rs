@@ -0,0 +1,256 @@
+use rand::{Rng, SeedableRng, StdRng};
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, Money};
+use engine::{self, Log};
+use game::{Error, GameState};
+use Event;
+use SeatId;
+use Sim;
+
+/** Value estimate
+ **
+ ** Expected chips won by a seat from a state of the game, every private card being known. A
+ ** closure can wrap any estimator (a strength or a Q network).
+ **/
+pub trait ValueEstimate {
+  fn value(&self, state: &GameState, seat_id: SeatId) -> f32;
+}
+
+impl<F: Fn(&GameState, SeatId) -> f32> ValueEstimate for F {
+  fn value(&self, state: &GameState, seat_id: SeatId) -> f32 {
+    self(state, seat_id)
+  }
+}
+
+/** Players checking down from the state, averaged over `samples` runouts of the table cards.
+ **
+ ** The runouts are seeded by the cards, a state always has the same value.
+ **/
+pub struct ValueShowdown {
+  pub samples: usize,
+}
+
+impl ValueEstimate for ValueShowdown {
+  fn value(&self, state: &GameState, seat_id: SeatId) -> f32 {
+    let mut seed = [0; 32];
+    let cards = state.players_init.iter().flat_map(|&(_, _, ref cards)| cards.iter());
+    for (i, card) in cards.chain(state.table_cards.iter()).take(32).enumerate() {
+      seed[i] = card.as_u8() + 1;
+    }
+    let ref mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    let runouts = table_outcomes(rng, state, table_cards_missing(state), self.samples);
+    let values: f32 = runouts.iter().map(|cards| showdown(state, cards, seat_id)).sum();
+
+    values / runouts.len() as f32
+  }
+}
+
+// Chips won by a seat once the given cards are added to the table, remaining players going to
+// the showdown with their current pots.
+fn showdown(state: &GameState, cards: &[Card], seat_id: SeatId) -> f32 {
+  let mut table_cards = state.table_cards.clone();
+  table_cards.extend(cards.iter());
+
+  let score = if state.is_terminal() {
+    let mut state = state.clone();
+    state.table_cards = table_cards;
+    state.score().unwrap()
+  } else {
+    engine::table_score(
+      state.sim,
+      state.evaluator,
+      &state.players_init,
+      &table_cards,
+      state.player_first,
+      Some((state.player_funds.clone(), state.player_pots.clone(), state.player_states.clone())),
+    )
+  };
+
+  chips(&state.players_init, &score.player_funds, seat_id)
+}
+
+fn chips(
+  players_init: &Vec<(SeatId, Money, Vec<Card>)>,
+  player_funds: &Vec<(SeatId, Money)>,
+  seat_id: SeatId,
+) -> f32 {
+  let &(_, m_init, _) = players_init.iter().find(|&&(s, _, _)| s == seat_id).unwrap();
+  let &(_, m_final) = player_funds.iter().find(|&&(s, _)| s == seat_id).unwrap();
+  (m_final - m_init) as f32
+}
+
+fn table_cards_missing(state: &GameState) -> usize {
+  let rounds = state.sim.profile.rounds.len();
+  state.table_cards_size(rounds - 1) - state.table_cards.len()
+}
+
+// Every combination of `size` cards out of the remaining deck, or `samples` random draws when
+// there are more.
+fn table_outcomes<R: Rng>(
+  rng: &mut R,
+  state: &GameState,
+  size: usize,
+  samples: usize,
+) -> Vec<Vec<Card>> {
+  use anna_utils::math::combinations;
+
+  if size == 0 {
+    return vec![Vec::new()];
+  }
+
+  let mut deck = state.deck_remaining();
+
+  let mut count = 1.;
+  for i in 0..size {
+    count = count * (deck.len() - i) as f64 / (i + 1) as f64;
+  }
+
+  if count <= samples as f64 {
+    combinations(deck.len(), size).iter().map(|xs| xs.iter().map(|&i| deck[i]).collect()).collect()
+  } else {
+    (0..samples)
+      .map(|_| {
+        rng.shuffle(&mut deck);
+        deck[..size].to_vec()
+      })
+      .collect()
+  }
+}
+
+/** AIVAT
+ **
+ ** Unbiased, lower variance estimate of the chips won by a seat. Along the logged game, the value
+ ** gained by every chance event (deals) and by every action of the seat is replaced by its
+ ** expectation, given the deal distribution and the known policy of the seat. Only the luck of
+ ** the opponents actions remains in the corrected result.
+ **
+ ** ref: https://arxiv.org/abs/1612.06915
+ **/
+pub struct Aivat<'a, V: 'a> {
+  pub value: &'a V,
+  pub samples: usize, // Deals sampled when they are too many to be enumerated.
+}
+
+impl<'a, V: ValueEstimate> Aivat<'a, V> {
+  pub fn new(value: &'a V, samples: usize) -> Aivat<'a, V> {
+    Aivat { value: value, samples: samples.max(1) }
+  }
+
+  // Terminal states are valued by their payoff.
+  fn state_value(&self, state: &GameState, seat_id: SeatId) -> f32 {
+    if state.is_terminal() {
+      let score = state.score().unwrap();
+      chips(&state.players_init, &score.player_funds, seat_id)
+    } else {
+      self.value.value(state, seat_id)
+    }
+  }
+
+  /** Corrected chips won by `seat_id` in a logged game.
+   **
+   ** `policy` gives the probabilities by action class of the seat at its decisions, the illegal
+   ** actions being ignored. A policy without any legal action is taken as uniform.
+   **/
+  pub fn correct<R: Rng, F: FnMut(&GameState) -> Vec<f32>>(
+    &self,
+    rng: &mut R,
+    sim: &'a Sim<'a>,
+    evaluator: &'a Eval,
+    log: &Log,
+    player_first: SeatId,
+    seat_id: SeatId,
+    mut policy: F,
+  ) -> Result<f32, Error> {
+    let mut state = GameState::new(sim, evaluator, &log.players_init, &Vec::new(), player_first)?;
+    let mut correction = 0.;
+
+    // Private cards.
+    let player_funds: Vec<(SeatId, Money)> =
+      log.players_init.iter().map(|&(seat_id, m, _)| (seat_id, m)).collect();
+    let mut expected = 0.;
+    for _ in 0..self.samples {
+      let (players_init, _) = engine::table_deal(rng, sim, &player_funds);
+      let state = GameState::new(sim, evaluator, &players_init, &Vec::new(), player_first)?;
+      expected += self.state_value(&state, seat_id);
+    }
+    correction += self.state_value(&state, seat_id) - expected / self.samples as f32;
+
+    // Blinds are already in the state.
+    for event in log.events.iter().skip(state.events.len()) {
+      match event {
+        // Dealt from the logged table cards by `deal`.
+        &Event::Table { .. } => {}
+        &Event::Play(ref act) => {
+          correction += self.deal(rng, &mut state, &log.table_cards, seat_id)?;
+
+          if act.seat_id == seat_id {
+            let legal = state.legal_actions();
+            let probs = policy(&state);
+            let total: f32 = legal.iter().map(|&a| probs.get(a as usize).unwrap_or(&0.)).sum();
+
+            let mut expected = 0.;
+            let mut taken = 0.;
+            for &action_i in legal.iter() {
+              let value = self.state_value(&state.apply(action_i)?, seat_id);
+              let p =
+                if total > 0. { probs[action_i as usize] / total } else { 1. / legal.len() as f32 };
+              expected += p * value;
+              if action_i == act.action {
+                taken = value;
+              }
+            }
+            correction += taken - expected;
+          }
+
+          state.apply_mut(act.action)?;
+        }
+        // Summary of the game.
+        _ => {}
+      }
+    }
+
+    // Table cards left, for an all-in showdown.
+    correction += self.deal(rng, &mut state, &log.table_cards, seat_id)?;
+
+    let payoff = state
+      .payoffs()
+      .and_then(|payoffs| payoffs.iter().find(|&&(s, _)| s == seat_id).map(|&(_, m)| m as f32))
+      .ok_or(Error::Sementic(format!("Game of the log is not over. seat {}", seat_id)))?;
+
+    Ok(payoff - correction)
+  }
+
+  // Deal the logged table cards until a player is to act, returning the luck of the deals.
+  fn deal<R: Rng>(
+    &self,
+    rng: &mut R,
+    state: &mut GameState<'a>,
+    table_cards: &[Card],
+    seat_id: SeatId,
+  ) -> Result<f32, Error> {
+    let mut correction = 0.;
+
+    while state.is_chance() {
+      let size = state.sim.profile.rounds[state.round_id];
+
+      let n = state.table_cards.len();
+      let cards = table_cards.get(n..n + size).ok_or(Error::Sementic(format!(
+        "Missing table cards in the log. round {}",
+        state.round_id
+      )))?;
+
+      let outcomes = table_outcomes(rng, state, size, self.samples);
+      let mut expected = 0.;
+      for outcome in outcomes.iter() {
+        expected += self.state_value(&state.apply_chance(outcome)?, seat_id);
+      }
+
+      *state = state.apply_chance(cards)?;
+      correction += self.state_value(state, seat_id) - expected / outcomes.len() as f32;
+    }
+
+    Ok(correction)
+  }
+}
@@ -0,0 +1,345 @@
+use rand::{Rng, SeedableRng, StdRng};
+use std::mem::replace;
+use std::time::Instant;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, classifiers::ActionLimit, ActionClass, Money};
+use engine::{table_run, Error, Score, TableView};
+use players::{Players, Table};
+use Event;
+use SeatId;
+use Sim;
+use State;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+  pub game: usize, // Index of the game since the audit started.
+  pub round_id: usize,
+  pub seat_id: SeatId,
+  pub message: String,
+}
+
+/** Table checking the invariants of the engine around every decision.
+ **
+ ** - chips are conserved, funds and pots summing to the funds given at `init`
+ ** - pots never decrease during a game
+ ** - the actions accepted by `table_run` are the legal ones of `ActionClass::normalize` (strict
+ **   mode only, other modes tolerating illegal folds and bets)
+ ** - `table_target_by` is a seat in play at the target, or all-in below it
+ **
+ ** Violations are kept in `violations`, the game playing on.
+ **/
+pub struct Audit<'a, T> {
+  pub table: T,
+  sim: &'a Sim<'a>,
+  games: usize,
+  funds: u32,
+  pots: Vec<Money>,
+  decision: (usize, SeatId),
+  pub violations: Vec<Violation>,
+}
+
+impl<'a, T> Audit<'a, T> {
+  pub fn new(table: T, sim: &'a Sim<'a>) -> Audit<'a, T> {
+    Audit {
+      table: table,
+      sim: sim,
+      games: 0,
+      funds: 0,
+      pots: Vec::new(),
+      decision: (0, 0),
+      violations: Vec::new(),
+    }
+  }
+
+  // Record a violation at the last decision, e.g. for a game aborted by the engine.
+  pub fn violation(&mut self, message: String) -> () {
+    let (round_id, seat_id) = self.decision;
+    self.violations.push(Violation {
+      game: self.games.saturating_sub(1),
+      round_id: round_id,
+      seat_id: seat_id,
+      message: message,
+    });
+  }
+
+  fn target_by_check(&mut self, view: &TableView) -> () {
+    if let Some(seat_id) = view.table_target_by {
+      let pot = view.player_pots[seat_id];
+      let owned = match view.player_states[seat_id] {
+        State::Play { all_in: false, .. } => pot == view.table_target,
+        State::Play { all_in: true, .. } => (pot - view.table_target) <= 0,
+        _ => false,
+      };
+
+      if !owned {
+        self.violation(format!(
+          "Target {} owned by seat {} with pot {} ({:?}).",
+          view.table_target, seat_id, pot, view.player_states[seat_id]
+        ));
+      }
+    }
+  }
+
+  fn mask_check(&mut self, view: &TableView) -> () {
+    let sim = self.sim;
+    if !sim.strict {
+      return;
+    }
+
+    let mask = sim.action_class.normalize(
+      sim.blind_biggest,
+      view.round_id,
+      view.table_target,
+      if view.raisable { Some(view.table_target_raise) } else { None },
+      view.player_funds[view.seat_id],
+      view.player_pots[view.seat_id],
+    );
+
+    for action_i in 0..sim.action_class.size() {
+      let raisable = view.raisable || !sim.action_class.is_raise(action_i as u8);
+      let accepted = raisable
+        && table_run::<()>(
+          sim,
+          view.round_id,
+          &mut view.player_funds.to_vec(),
+          &mut view.player_pots.to_vec(),
+          &mut view.player_states.to_vec(),
+          &mut view.table_target.clone(),
+          &mut view.table_target_raise.clone(),
+          &mut view.table_target_by.clone(),
+          view.seat_id,
+          action_i as u8,
+        )
+        .is_ok();
+
+      if accepted != mask.contains(&action_i) {
+        self.violation(format!(
+          "Action {} {} by the engine, {} by `normalize`. fund {}, pot {}, target {}",
+          action_i,
+          if accepted { "accepted" } else { "rejected" },
+          if accepted { "illegal" } else { "legal" },
+          view.player_funds[view.seat_id],
+          view.player_pots[view.seat_id],
+          view.table_target
+        ));
+      }
+    }
+  }
+}
+
+impl<'a, T: Players<E>, E> Players<E> for Audit<'a, T> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.games += 1;
+    self.funds = player_funds.iter().map(|m| m.unpack()).sum();
+    self.pots = vec![Money::zero(); player_funds.len()];
+    self.decision = (0, player_first);
+
+    self.table.init(blinds, player_first, player_funds, playing_hands)
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, E> {
+    self.table.play(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.table.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.table.game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.table.deadline(seat_id, deadline)
+  }
+}
+
+impl<'a, T: Table<E>, E> Table<E> for Audit<'a, T> {
+  fn game_start(&mut self, blinds: Vec<(usize, Money)>) -> Result<(), E> {
+    self.table.game_start(blinds)
+  }
+
+  fn round_start(
+    &mut self,
+    evaluator: &Eval,
+    round_id: usize,
+    table_target: Money,
+  ) -> Result<Vec<Card>, E> {
+    self.table.round_start(evaluator, round_id, table_target)
+  }
+
+  fn audit_decision(&mut self, view: &TableView) -> () {
+    self.decision = (view.round_id, view.seat_id);
+
+    self.target_by_check(view);
+    self.mask_check(view);
+  }
+
+  fn audit_action(&mut self, view: &TableView, action_i: u8) -> () {
+    let funds: u32 = view.player_funds.iter().chain(view.player_pots).map(|m| m.unpack()).sum();
+    if funds != self.funds {
+      self.violation(format!(
+        "Chips not conserved by action {}: {} for {} at init.",
+        action_i, funds, self.funds
+      ));
+    }
+
+    let pots_last = replace(&mut self.pots, view.player_pots.to_vec());
+    for (seat_id, (&pot, &pot_last)) in view.player_pots.iter().zip(pots_last.iter()).enumerate() {
+      if (pot - pot_last) < 0 {
+        self.violation(format!(
+          "Pot of seat {} decreased by action {}: {} to {}.",
+          seat_id, action_i, pot_last, pot
+        ));
+      }
+    }
+
+    self.target_by_check(view);
+  }
+}
+
+/** Play an audited game of random players, on a profile (limit or no-limit), player count, stacks
+ ** and mode drawn from `seed`.
+ **
+ ** Stacks are either deep, short (a few big blinds, all-ins being frequent) or mixed. The game is
+ ** replayed as is given the same seed. A game aborted by the engine is a violation, the players
+ ** only playing legal actions.
+ **/
+pub fn fuzz_game(seed: u64) -> Vec<Violation> {
+  use anna_model::classifiers::{ActionKuhn, ActionNoLimit};
+  use anna_model::profile::{
+    profile_cochard, profile_kuhn, profile_leduc, profile_leduc_french, profile_texas_limit,
+    profile_texas_nolimit,
+  };
+  use engine::{table_deal, table_game_simulate_, table_score};
+  use players::{PlayersRand, TableStatic};
+  use OddChip;
+
+  let mut seed_bytes = [0; 32];
+  for i in 0..8 {
+    seed_bytes[i] = (seed >> (8 * i)) as u8;
+  }
+  let mut rng: StdRng = SeedableRng::from_seed(seed_bytes);
+
+  let blind = Money::new(1, 0);
+  let profile = match rng.gen_range(0, 6) {
+    0 => profile_kuhn(rng.gen_range(2, 4)),
+    1 => profile_leduc(rng.gen_range(2, 6)),
+    2 => profile_leduc_french(rng.gen_range(2, 10)),
+    3 => profile_cochard(rng.gen_range(2, 10)),
+    4 => profile_texas_limit(rng.gen_range(2, 10), blind / 2, blind),
+    _ => profile_texas_nolimit(rng.gen_range(2, 10), blind / 2, blind),
+  };
+
+  let action_kuhn = ActionKuhn {};
+  let action_limit = ActionLimit { raises: profile.limit.clone().map_or(vec![], |l| l.raises) };
+  let action_nolimit = ActionNoLimit { raises: vec![1, 2, 4] };
+  let action_class: &ActionClass = match profile.id.as_str() {
+    "kuhn" => &action_kuhn,
+    "texas_nolimit" => &action_nolimit,
+    _ => &action_limit,
+  };
+
+  let eval_naive = Eval::naive();
+  let eval_texas = Eval::texas();
+  let eval: &Eval = if profile.id.starts_with("texas") { &eval_texas } else { &eval_naive };
+
+  let players = profile.players;
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind,
+    profile: profile,
+    strict: rng.gen_bool(0.75),
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let stacks = rng.gen_range(0, 3);
+  let funds: Vec<(SeatId, Money)> = (0..players)
+    .map(|seat_id| {
+      let bigs = match stacks {
+        0 => rng.gen_range(50, 300),
+        1 => rng.gen_range(1, 8),
+        _ => rng.gen_range(1, 100),
+      };
+      (seat_id, blind * bigs + Money::from_u32(rng.gen_range(0, 100)))
+    })
+    .collect();
+  let player_first = rng.gen_range(0, players);
+
+  let rng_players: StdRng = SeedableRng::from_seed(rng.gen());
+  let ref mut players_rand = PlayersRand::new(rng_players, action_class, blind);
+
+  let (ref players_init, ref table_cards) = table_deal(&mut rng, sim, &funds);
+
+  let mut audit = Audit::new(
+    TableStatic {
+      rounds: sim.profile.rounds.clone(),
+      table_cards: table_cards.clone(),
+      players: players_rand,
+    },
+    sim,
+  );
+
+  match table_game_simulate_(sim, eval, &mut audit, players_init, player_first) {
+    Ok((_, _, result)) => {
+      let score: Score = table_score(sim, eval, players_init, table_cards, player_first, result);
+
+      let funds_init: u32 = funds.iter().map(|&(_, m)| m.unpack()).sum();
+      let funds_end: u32 = score.player_funds.iter().map(|&(_, m)| m.unpack()).sum();
+      if funds_end != funds_init {
+        audit.violation(format!(
+          "Chips not conserved by the score: {} for {}.",
+          funds_end, funds_init
+        ));
+      }
+    }
+    Err((_, _, e)) => {
+      let e: Error<()> = e;
+      audit.violation(format!("Game aborted: {:?}", e));
+    }
+  }
+
+  audit.violations
+}
@@ -0,0 +1,61 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+extern crate anna_utils;
+
+// Serve ACPC clients with the engine and `profile_texas_limit`.
+//
+//   acpc_dealer <port> <hands> [players]
+//
+// The game definition to give to the clients is written to `texas_limit.<players>p.game`.
+fn main() -> () {
+  use std::{env::args, fs::File, io::Write, net::TcpListener};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_texas_limit, Money};
+  use anna_simulation::{
+    acpc::{Game, PlayersAcpc},
+    perf::{rate_format, run_benchmark},
+    OddChip, Sim,
+  };
+  use anna_utils::logging;
+
+  logging::init();
+
+  let port: u16 = args().nth(1).expect("Missing port.").parse().unwrap();
+  let hands: usize = args().nth(2).expect("Missing hands.").parse().unwrap();
+  let players: usize = args().nth(3).map(|x| x.parse().unwrap()).unwrap_or(2);
+
+  let blind_big = Money::new(1, 0);
+  let profile = profile_texas_limit(players, blind_big / 2, blind_big);
+
+  let ref action_class = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind_big,
+    profile: profile,
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let ref eval = Eval::texas();
+
+  let game_path = format!("texas_limit.{}p.game", players);
+  let mut game_file = File::create(&game_path).unwrap();
+  write!(game_file, "{}", Game::from_sim(sim)).unwrap();
+
+  let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
+  println!("Waiting for {} clients on port {} ({}).", players, port, game_path);
+
+  let ref mut players_acpc = PlayersAcpc::tcp_accept(&listener, action_class, players).unwrap();
+  let funds: Vec<_> = (0..players).map(|seat_id| (seat_id, blind_big * 200)).collect();
+  let rates = run_benchmark(sim, eval, &funds, hands, players_acpc);
+
+  for (seat_id, rate) in rates.iter().enumerate() {
+    println!("seat {}: {} mbb/hand", seat_id, rate_format(*rate));
+  }
+}
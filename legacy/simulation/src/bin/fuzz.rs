@@ -0,0 +1,44 @@
+extern crate rayon;
+
+extern crate anna_simulation;
+extern crate anna_utils;
+
+// Play random audited games, printing the seed of the games breaking an engine invariant.
+//
+//   fuzz [games] [seed_first]
+//
+// A game is replayed with `audit::fuzz_game(<seed>)`.
+fn main() -> () {
+  use rayon::prelude::*;
+  use std::env::args;
+  use std::process::exit;
+
+  use anna_simulation::audit::fuzz_game;
+  use anna_utils::logging;
+
+  logging::init();
+
+  let games: u64 = args().nth(1).map(|x| x.parse().unwrap()).unwrap_or(1_000_000);
+  let seed_first: u64 = args().nth(2).map(|x| x.parse().unwrap()).unwrap_or(0);
+
+  let seeds: Vec<u64> = (seed_first..seed_first + games)
+    .into_par_iter()
+    .filter_map(|seed| {
+      let violations = fuzz_game(seed);
+      for v in violations.iter() {
+        println!("seed {} round {} seat {}: {}", seed, v.round_id, v.seat_id, v.message);
+      }
+      if violations.is_empty() {
+        None
+      } else {
+        Some(seed)
+      }
+    })
+    .collect();
+
+  println!("{} games, {} breaking an invariant.", games, seeds.len());
+
+  if !seeds.is_empty() {
+    exit(1);
+  }
+}
@@ -0,0 +1,385 @@
+use rand::Rng;
+use std::collections::HashMap;
+use std::path::Path;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, ActionClass, Money};
+use anna_utils::bincode;
+use exploit::Strategy;
+use game::{CardsAbstraction, Error};
+use players::Players;
+use tree::{Node, NodeId, Tree};
+use Event;
+use SeatId;
+use Sim;
+
+/** CFR+
+ **
+ ** Counterfactual regret minimization with regrets floored at zero and alternating updates, run
+ ** over the full game tree of a small profile (Kuhn, Leduc). Strategies are indexed by action
+ ** class, keyed by information set.
+ **
+ ** ref: https://arxiv.org/abs/1407.5042
+ **/
+pub struct Cfr {
+  pub tree: Tree,
+  pub iterations: usize,
+  pub linear: bool, // Weight the average strategy by iteration.
+  actions_size: usize,
+  regrets: HashMap<String, Vec<f32>>,
+  strategy_sum: HashMap<String, Vec<f32>>,
+}
+
+impl Cfr {
+  pub fn new(
+    sim: &Sim,
+    evaluator: &Eval,
+    player_funds: &Vec<Money>,
+    linear: bool,
+  ) -> Result<Cfr, Error> {
+    let tree = Tree::build(sim, evaluator, player_funds, 0)?;
+    Ok(Cfr::from_tree(tree, sim.action_class.size(), linear))
+  }
+
+  pub fn from_tree(tree: Tree, actions_size: usize, linear: bool) -> Cfr {
+    Cfr {
+      tree: tree,
+      iterations: 0,
+      linear: linear,
+      actions_size: actions_size,
+      regrets: HashMap::new(),
+      strategy_sum: HashMap::new(),
+    }
+  }
+
+  pub fn run(&mut self, iterations: usize) -> () {
+    for _ in 0..iterations {
+      self.iterate();
+    }
+  }
+
+  pub fn iterate(&mut self) -> () {
+    self.iterations += 1;
+
+    let weight = if self.linear { self.iterations as f32 } else { 1. };
+
+    for seat_id in 0..self.tree.players {
+      let mut deltas = HashMap::new();
+
+      cfr_traverse(
+        &self.tree,
+        &self.regrets,
+        &mut deltas,
+        &mut self.strategy_sum,
+        self.actions_size,
+        self.tree.root,
+        seat_id,
+        weight,
+        1.,
+        1.,
+      );
+
+      // Floored once the whole iteration of the seat is done, its strategy being fixed meanwhile.
+      for (infoset, ds) in deltas {
+        let rs = self.regrets.entry(infoset).or_insert(vec![0.; ds.len()]);
+        for (r, d) in rs.iter_mut().zip(ds.iter()) {
+          *r = (*r + d).max(0.);
+        }
+      }
+    }
+  }
+
+  // Strategy of the current iteration.
+  pub fn strategy_current(&self) -> Strategy {
+    let mut strategy = Strategy::new();
+
+    for node in self.tree.nodes.iter() {
+      if let &Node::Decision { ref infoset, ref actions, .. } = node {
+        if !strategy.contains_key(infoset) {
+          let sigma = regret_matching(&self.regrets, infoset, actions.len());
+          let mut probs = vec![0.; self.actions_size];
+          for (&(action_i, _), pa) in actions.iter().zip(sigma) {
+            probs[action_i as usize] = pa;
+          }
+          strategy.insert(infoset.clone(), probs);
+        }
+      }
+    }
+
+    strategy
+  }
+
+  // Average strategy, converging to an equilibrium in 2 players games.
+  pub fn strategy(&self) -> Strategy {
+    strategy_average(&self.strategy_sum)
+  }
+}
+
+// Normalize strategy sums into action probabilities.
+pub fn strategy_average(strategy_sum: &HashMap<String, Vec<f32>>) -> Strategy {
+  strategy_sum
+    .iter()
+    .map(|(infoset, sums)| {
+      let total: f32 = sums.iter().sum();
+      let probs = if total > 0. { sums.iter().map(|x| x / total).collect() } else { sums.clone() };
+      (infoset.clone(), probs)
+    })
+    .collect()
+}
+
+// Counterfactual value of a node for the updated seat, `p` and `q` being the reach probabilities
+// of the seat and of the others (chance included). Regrets of the iteration are summed in `deltas`.
+fn cfr_traverse(
+  tree: &Tree,
+  regrets: &HashMap<String, Vec<f32>>,
+  deltas: &mut HashMap<String, Vec<f32>>,
+  strategy_sum: &mut HashMap<String, Vec<f32>>,
+  actions_size: usize,
+  node_id: NodeId,
+  seat_id: SeatId,
+  weight: f32,
+  p: f32,
+  q: f32,
+) -> f32 {
+  match &tree.nodes[node_id] {
+    &Node::Terminal { ref payoffs } => {
+      payoffs.iter().find(|&&(s, _)| s == seat_id).map(|&(_, m)| m as f32).unwrap_or(0.)
+    }
+    &Node::Chance { ref outcomes } => {
+      let mut value = 0.;
+      for &(_, pc, child) in outcomes {
+        value += pc
+          * cfr_traverse(
+            tree,
+            regrets,
+            deltas,
+            strategy_sum,
+            actions_size,
+            child,
+            seat_id,
+            weight,
+            p,
+            q * pc,
+          );
+      }
+      value
+    }
+    &Node::Decision { seat_id: s, ref infoset, ref actions } => {
+      let sigma = regret_matching(regrets, infoset, actions.len());
+
+      let mut values = Vec::with_capacity(actions.len());
+      for (&(_, child), &pa) in actions.iter().zip(sigma.iter()) {
+        let (p_child, q_child) = if s == seat_id { (p * pa, q) } else { (p, q * pa) };
+        values.push(cfr_traverse(
+          tree,
+          regrets,
+          deltas,
+          strategy_sum,
+          actions_size,
+          child,
+          seat_id,
+          weight,
+          p_child,
+          q_child,
+        ));
+      }
+
+      let value: f32 = values.iter().zip(sigma.iter()).map(|(v, pa)| v * pa).sum();
+
+      if s == seat_id {
+        let ds = deltas.entry(infoset.clone()).or_insert(vec![0.; actions.len()]);
+        for (d, v) in ds.iter_mut().zip(values.iter()) {
+          *d += q * (v - value);
+        }
+
+        let sums = strategy_sum.entry(infoset.clone()).or_insert(vec![0.; actions_size]);
+        for (&(action_i, _), pa) in actions.iter().zip(sigma.iter()) {
+          sums[action_i as usize] += weight * p * pa;
+        }
+      }
+
+      value
+    }
+  }
+}
+
+fn regret_matching(regrets: &HashMap<String, Vec<f32>>, infoset: &String, size: usize) -> Vec<f32> {
+  match regrets.get(infoset) {
+    Some(rs) if rs.iter().sum::<f32>() > 0. => {
+      let total: f32 = rs.iter().sum();
+      rs.iter().map(|r| r / total).collect()
+    }
+    _ => vec![1. / size as f32; size],
+  }
+}
+
+pub fn strategy_load(path: &Path) -> Result<Strategy, bincode::Error> {
+  bincode::deserialize_from_file(path)
+}
+
+pub fn strategy_save(path: &Path, strategy: &Strategy) -> Result<(), bincode::Error> {
+  bincode::serialize_into_file(path, strategy)
+}
+
+/** Players sampling their actions from a strategy table.
+ **
+ ** Unknown information sets are played uniformly over the legal actions.
+ **/
+#[derive(Clone)]
+pub struct PlayersStrategy<'a, R: 'a> {
+  rng: R,
+  action_class: &'a ActionClass,
+  pub strategy: Strategy,
+  abstraction: Option<&'a CardsAbstraction>,
+  blind_biggest: Money,
+  players: usize,
+  player_first: SeatId,
+  players_cards: HashMap<SeatId, Vec<Card>>,
+  players_events: HashMap<SeatId, Vec<Event>>,
+}
+
+impl<'a, R: Rng> PlayersStrategy<'a, R> {
+  pub fn new(rng: R, action_class: &'a ActionClass, strategy: Strategy) -> PlayersStrategy<'a, R> {
+    PlayersStrategy {
+      rng: rng,
+      action_class: action_class,
+      strategy: strategy,
+      abstraction: None,
+      blind_biggest: Money::zero(),
+      players: 0,
+      player_first: 0,
+      players_cards: HashMap::new(),
+      players_events: HashMap::new(),
+    }
+  }
+
+  // Strategy keyed by card buckets, as trained by `mccfr::Mccfr` with an abstraction.
+  pub fn with_abstraction(
+    rng: R,
+    action_class: &'a ActionClass,
+    strategy: Strategy,
+    abstraction: &'a CardsAbstraction,
+  ) -> PlayersStrategy<'a, R> {
+    let mut players = PlayersStrategy::new(rng, action_class, strategy);
+    players.abstraction = Some(abstraction);
+    players
+  }
+
+  // Probabilities of the legal actions, `events` being the whole history seen by the seat.
+  fn probs(
+    &self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Option<Vec<f32>> {
+    use game::{infoset_key, infoset_key_bucket};
+
+    let cards = self.players_cards.get(&seat_id)?;
+    let key = match self.abstraction {
+      None => infoset_key(self.players, self.player_first, seat_id, cards, events),
+      Some(abstraction) => {
+        infoset_key_bucket(self.players, self.player_first, seat_id, cards, events, abstraction)
+      }
+    };
+
+    let mask = self.action_class.normalize(
+      self.blind_biggest,
+      round_id,
+      table_target,
+      table_target_raise,
+      player_fund,
+      player_pots[seat_id],
+    );
+
+    let mut probs: Vec<f32> = (0..self.action_class.size())
+      .map(|i| match self.strategy.get(&key) {
+        Some(xs) if mask.contains(&i) => *xs.get(i).unwrap_or(&0.),
+        _ => 0.,
+      })
+      .collect();
+
+    if probs.iter().all(|&p| p <= 0.) {
+      probs =
+        (0..self.action_class.size()).map(|i| if mask.contains(&i) { 1. } else { 0. }).collect();
+    }
+
+    let total: f32 = probs.iter().sum();
+    Some(probs.iter().map(|p| p / total).collect())
+  }
+}
+
+impl<'a, R: Rng> Players<()> for PlayersStrategy<'a, R> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.blind_biggest = *blinds.iter().max().unwrap();
+    self.players = player_funds.len();
+    self.player_first = player_first;
+    self.players_cards = playing_hands.iter().cloned().collect();
+    self.players_events = HashMap::new();
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    use anna_utils::math::sample;
+
+    // Events start with the last action of the player after its first play.
+    self.players_events.entry(seat_id).or_insert(Vec::new()).extend(events.iter().cloned());
+
+    let probs = self
+      .probs(
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        &self.players_events[&seat_id],
+      )
+      .ok_or(())?;
+
+    Ok(sample(&mut self.rng, probs) as u8)
+  }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    let mut history = self.players_events.get(&seat_id).cloned().unwrap_or(Vec::new());
+    history.extend(events.iter().cloned());
+
+    self
+      .probs(
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        &history,
+      )
+      .unwrap_or(Vec::new())
+  }
+}
@@ -11,6 +11,7 @@ use anna_eval::Eval;
 use anna_model::{cards::Card, Action, Money};
 use players::{Players, Table};
 use Event;
+use OddChip;
 use SeatId;
 use Sim;
 use State;
@@ -22,6 +23,13 @@ pub enum Error<E> {
   Sementic(String),
   InsufficientBringIn { seat_id: SeatId },
   InsufficientFund { seat_id: SeatId, fund: Money, bet: Money },
+  InsufficientPot { seat_id: SeatId, pot: Money, unpledge: Money },
+  InactiveSeat { seat_id: SeatId },
+  NoActiveSeat { seat_id: SeatId },
+  PotOffTarget { seat_id: SeatId, pot: Money, target: Money },
+  InvalidAction { seat_id: SeatId, action: u8 },
+  InvalidRaise { seat_id: SeatId, raise: i32, raise_min: Money },
+  FundsConservation { funds_init: u32, funds: u32 },
   Play(E),
 }
 
@@ -40,117 +48,186 @@ pub struct Log {
 
 pub type Rounds = Vec<Vec<(SeatId, Action, Option<Money>)>>;
 
-#[derive(Clone, Debug)]
+// State of the table as seen by the engine around a decision, given to the audit hooks of `Table`.
+pub struct TableView<'a> {
+  pub round_id: usize,
+  pub seat_id: SeatId,
+  pub raisable: bool,
+  pub player_funds: &'a [Money],
+  pub player_pots: &'a [Money],
+  pub player_states: &'a [State],
+  pub table_target: Money,
+  pub table_target_raise: Money,
+  pub table_target_by: Option<SeatId>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Score {
   pub player_funds: Vec<(SeatId, Money)>,
   pub pot: Money,
+  pub pots: Vec<Pot>, // Main pot first, followed by side pots.
   pub winners: Vec<SeatId>,
   pub winners_score: f32,
 }
 
-pub fn funds_update(
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Pot {
+  pub amount: Money,
+  pub seats: Vec<SeatId>, // Non-folded seats eligible to win the pot.
+  pub winners: Vec<SeatId>,
+  pub awards: Vec<(SeatId, Money)>,
+}
+
+// Split the players pledges in a main pot and side pots, then elect the winners of each pot using
+// the finalists scores.
+pub fn pots_split(
   sim: &Sim,
-  player_funds: &mut Vec<Money>,
   player_pots: &[Money],
-  winners: &Vec<SeatId>,
-) -> () {
-  let prorata = move |player_funds: &mut Vec<Money>, players: &Vec<SeatId>, money: u32| {
-    let pot_prorata: Money = Money::from_u32(money / players.len() as u32);
-    let pot_remaining: u32 = money - (pot_prorata.unpack() * players.len() as u32);
-
-    if pot_remaining > 0 {
-      let mut seat_id: usize = players[0] as usize;
-      let mut m = player_funds.iter().max().unwrap().unpack();
-      for s in players {
-        let pm = player_funds[*s as usize].unpack();
-        if pm < m {
-          seat_id = *s as usize;
-          m = pm;
-        }
-      }
-
-      player_funds[seat_id] = player_funds[seat_id] + Money::from_u32(pot_remaining);
-    }
-
-    for &seat_id in players {
-      player_funds[seat_id as usize] = player_funds[seat_id as usize] + pot_prorata;
-    }
-  };
-
-  let player_pots_max = player_pots.iter().enumerate().map(|(_, m)| m).max().unwrap();
-
-  if winners.iter().any(|&seat_id| player_pots[seat_id as usize] != *player_pots_max) {
-    // Compute and distribute side pots.
-    let mut xs: Vec<(u32)> =
-      winners.iter().map(|&seat_id| player_pots[seat_id as usize].unpack()).collect();
-
-    xs.sort();
-    xs.dedup();
-
-    let winners_target = xs.last().unwrap().clone();
-
-    let mut pot_target_last: u32 = 0;
+  player_states: &[State],
+  finalists: &[(SeatId, f32)],
+  dealer: SeatId,
+) -> Vec<Pot> {
+  use std::cmp::{min, Ordering};
+
+  let mut levels: Vec<u32> = player_pots.iter().map(|m| m.unpack()).filter(|&m| m > 0).collect();
+  levels.sort();
+  levels.dedup();
+
+  let mut pots: Vec<Pot> = Vec::new();
+  let mut level_last: u32 = 0;
+  let mut amount_carry: u32 = 0; // Pledged by folded players only, goes to the next pot.
+
+  for level in levels {
+    let amount: u32 = amount_carry
+      + player_pots
+        .iter()
+        .map(|m| min(m.unpack(), level) - min(m.unpack(), level_last))
+        .sum::<u32>();
 
-    for pot_target in xs {
-      let mut pot_side: u32 = 0;
+    let seats: Vec<SeatId> = finalists
+      .iter()
+      .filter(|&&(seat_id, _)| player_pots[seat_id].unpack() >= level)
+      .map(|&(seat_id, _)| seat_id)
+      .collect();
 
-      let pot_target_delta = pot_target - pot_target_last;
+    level_last = level;
 
-      for seat_id in 0..sim.profile.players {
-        let player_pot = player_pots[seat_id].unpack();
-        if player_pot > 0 {
-          if player_pot >= pot_target {
-            pot_side += pot_target_delta;
-          } else if player_pot > pot_target_last {
-            pot_side += player_pot - pot_target_last;
-          }
+    if seats.is_empty() {
+      amount_carry = amount;
+    } else {
+      amount_carry = 0;
+      match pots.last_mut() {
+        Some(ref mut pot) if pot.seats == seats => {
+          pot.amount = pot.amount + Money::from_u32(amount);
+          continue;
         }
+        _ => {}
       }
+      pots.push(Pot {
+        amount: Money::from_u32(amount),
+        seats: seats,
+        winners: Vec::new(),
+        awards: Vec::new(),
+      });
+    }
+  }
 
-      let winners_side: Vec<SeatId> = winners
-        .iter()
-        .filter(|&seat_id| player_pots[*seat_id as usize].unpack() >= pot_target)
-        .cloned()
-        .collect();
+  if amount_carry > 0 {
+    if let Some(pot) = pots.last_mut() {
+      pot.amount = pot.amount + Money::from_u32(amount_carry);
+    }
+  }
+
+  for pot in pots.iter_mut() {
+    let scores: Vec<(SeatId, f32)> =
+      finalists.iter().filter(|&&(seat_id, _)| pot.seats.contains(&seat_id)).cloned().collect();
+
+    let score_max = scores
+      .iter()
+      .map(|&(_, score)| score)
+      .max_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+      .unwrap();
+
+    let winners: Vec<SeatId> = scores
+      .iter()
+      .filter(|&&(_, score)| score == score_max)
+      .map(|&(seat_id, _)| seat_id)
+      .collect();
+    pot.winners = odd_chip_order(sim, player_states, dealer, &winners);
+
+    // Split pro rata, remaining chips are given one by one following the odd chip order.
+    let winners_len = pot.winners.len() as u32;
+    let pot_prorata = pot.amount.unpack() / winners_len;
+    let pot_remaining = pot.amount.unpack() - (pot_prorata * winners_len);
+
+    pot.awards = pot
+      .winners
+      .iter()
+      .enumerate()
+      .map(|(i, &seat_id)| {
+        let odd = if (i as u32) < pot_remaining { 1 } else { 0 };
+        (seat_id, Money::from_u32(pot_prorata + odd))
+      })
+      .collect();
+  }
 
-      prorata(player_funds, &winners_side, pot_side);
+  pots
+}
 
-      pot_target_last = pot_target;
+// Sort the winners of a pot in the order they should receive odd chips.
+pub fn odd_chip_order(
+  sim: &Sim,
+  player_states: &[State],
+  dealer: SeatId,
+  winners: &[SeatId],
+) -> Vec<SeatId> {
+  let mut xs = winners.to_vec();
+
+  match sim.odd_chip {
+    OddChip::ButtonLeft => {
+      let players = player_states.len();
+      xs.sort_by_key(|&seat_id| (seat_id + players - (dealer + 1)) % players);
+    }
+    OddChip::SuitLowest => {
+      xs.sort_by_key(|&seat_id| match player_states[seat_id] {
+        State::Play { ref cards, .. } => cards.iter().map(|c| (c.suit, c.value)).min(),
+        _ => None,
+      });
     }
+  }
 
-    // If no one did match the last raises while the winner did all-in before them,
-    // we'll give money back to those last raises after distributing the winner(s)
-    // part.
-    if winners_target < player_pots_max.unpack() {
-      let xs: Vec<usize> = player_pots
-        .iter()
-        .enumerate()
-        .filter_map(|(seat_id, &m)| if m.unpack() > winners_target { Some(seat_id) } else { None })
-        .collect();
+  xs
+}
 
-      for seat_id in xs {
-        let target_max = player_pots[seat_id].unpack();
-        player_funds[seat_id] =
-          player_funds[seat_id] + Money::from_u32(target_max - winners_target);
-      }
+pub fn funds_update(
+  sim: &Sim,
+  player_funds: &mut Vec<Money>,
+  player_pots: &[Money],
+  player_states: &[State],
+  finalists: &[(SeatId, f32)],
+  dealer: SeatId,
+) -> Vec<Pot> {
+  let pots = pots_split(sim, player_pots, player_states, finalists, dealer);
+
+  for pot in pots.iter() {
+    for &(seat_id, money) in pot.awards.iter() {
+      player_funds[seat_id] = player_funds[seat_id] + money;
     }
-  } else {
-    // Distribute pot pro rata.
-    let pot: u32 = player_pots.iter().map(|m| m.unpack()).sum();
-    prorata(player_funds, winners, pot);
   }
+
+  pots
 }
 
 pub fn game_finish(
   evaluator: &Eval,
   table_cards: &[Card],
   player_states: &[State],
-) -> (Vec<(usize, Vec<Card>, bool)>, Vec<SeatId>, f32) {
-  let finalists: Vec<(usize, Vec<Card>, bool)> = player_states
+) -> (Vec<(usize, Vec<Card>, bool, f32)>, Vec<SeatId>, f32) {
+  let mut finalists: Vec<(usize, Vec<Card>, bool, f32)> = player_states
     .iter()
     .enumerate()
     .filter_map(|(i, s)| match s {
-      &State::Play { ref cards, all_in } => Some((i, cards.clone(), all_in)),
+      &State::Play { ref cards, all_in } => Some((i, cards.clone(), all_in, 0.)),
       _ => None,
     })
     .collect();
@@ -160,25 +237,25 @@ pub fn game_finish(
   let winners = if finalists.len() > 1 {
     // Compute score and elect winner(s)
     let mut winners: Vec<SeatId> = Vec::new();
-    for &(seat_id, ref player_cards, _) in finalists.iter() {
+    for &mut (seat_id, ref player_cards, _, ref mut score) in finalists.iter_mut() {
       let mut hand = Vec::new();
       hand.extend(table_cards);
       hand.extend(player_cards);
 
-      let score = evaluator.score(&hand);
-      if score >= winners_score {
-        if score == winners_score {
+      *score = evaluator.score(&hand);
+      if *score >= winners_score {
+        if *score == winners_score {
           winners.push(seat_id);
         } else {
           winners = vec![seat_id];
-          winners_score = score;
+          winners_score = *score;
         }
       }
     }
 
     winners
   } else {
-    let winners: Vec<SeatId> = finalists.iter().map(|&(i, _, _)| i).collect();
+    let winners: Vec<SeatId> = finalists.iter().map(|&(i, _, _, _)| i).collect();
     winners
   };
 
@@ -208,8 +285,6 @@ pub fn table_simulate_<E, R: Rng>(
   use rounds;
   use std::collections::HashSet;
 
-  let player_funds_sum: u32 = player_funds_init.iter().map(|&(_, m)| m.unpack()).sum::<u32>();
-
   let ref mut player_funds: Vec<(SeatId, Money)> = player_funds_init.clone();
   let mut actives: HashSet<SeatId> =
     player_funds_init.iter().map(|&(seat_id, _)| seat_id).collect();
@@ -237,6 +312,14 @@ pub fn table_simulate_<E, R: Rng>(
     let (log, score) = table_game_simulate(rng, sim, evaluator, players, &funds, seat_id)
       .map_err(|(log, err)| (scores.clone(), log, err))?;
 
+    // Chips are neither created nor lost by a game.
+    let funds_init: u32 = funds.iter().map(|&(_, m)| m.unpack()).sum();
+    let funds_end: u32 = score.player_funds.iter().map(|&(_, m)| m.unpack()).sum();
+    if funds_end != funds_init {
+      let error = Error::FundsConservation { funds_init: funds_init, funds: funds_end };
+      return Err((scores.clone(), log, error));
+    }
+
     scores.push((log, score.clone()));
 
     if score.player_funds == funds {
@@ -254,9 +337,6 @@ pub fn table_simulate_<E, R: Rng>(
     //   println!("{} {:?}", scores.len(), player_funds);
     // }
 
-    // TODO Remove this assertion!
-    assert!(player_funds_sum == player_funds.iter().map(|&(_, m)| m.unpack()).sum::<u32>());
-
     for &(seat_id, money) in player_funds.iter() {
       if (sim.blind_biggest - money) >= 0 {
         actives.remove(&seat_id);
@@ -269,8 +349,6 @@ pub fn table_simulate_<E, R: Rng>(
     scores.truncate(n);
   }
 
-  assert!(player_funds_sum == player_funds.iter().map(|&(_, m)| m.unpack()).sum::<u32>());
-
   Ok((player_funds.clone(), scores.clone()))
 }
 
@@ -282,23 +360,21 @@ pub fn table_game_simulate<E, R: Rng>(
   player_funds_init: &Vec<(SeatId, Money)>,
   player_first: SeatId,
 ) -> Result<(Log, Score), (Log, Error<E>)> {
-  use players::TableStatic;
-
-  let ref mut players_init = Vec::new();
-
-  let mut cards: Vec<&Card> = sim.profile.deck.iter().collect();
-  rng.shuffle(&mut cards);
+  let (ref players_init, ref table_cards) = table_deal(rng, sim, player_funds_init);
 
-  let private_cards_size = sim.profile.rounds[0];
-
-  for &(seat_id, m) in player_funds_init {
-    let cs: Vec<&Card> = cards.drain(0..private_cards_size).collect();
-    players_init.push((seat_id, m, cs.iter().map(|&c| c.clone()).collect()));
-  }
+  table_game_play(sim, evaluator, players, players_init, table_cards, player_first)
+}
 
-  let table_cards_size = sim.profile.rounds.iter().sum::<usize>() - private_cards_size;
-  let ref table_cards: Vec<Card> =
-    cards.iter().take(table_cards_size).map(|&c| c.clone()).collect();
+// Play a game on a given deal, private cards along with the table cards of all rounds.
+pub fn table_game_play<E>(
+  sim: &Sim,
+  evaluator: &Eval,
+  players: &mut Players<E>,
+  players_init: &Vec<(SeatId, Money, Vec<Card>)>,
+  table_cards: &Vec<Card>,
+  player_first: SeatId,
+) -> Result<(Log, Score), (Log, Error<E>)> {
+  use players::TableStatic;
 
   let mut table = TableStatic {
     rounds: sim.profile.rounds.clone(),
@@ -321,22 +397,134 @@ pub fn table_game_simulate<E, R: Rng>(
       },
     )?;
 
-  let score = match result {
+  let score = table_score(sim, evaluator, players_init, table_cards, player_first, result);
+  let showdown = showdown_hands(players_init, &score);
+
+  table.game_end(&score, &showdown);
+
+  // Blinds open the first round.
+  let blinds: Vec<(SeatId, Money)> = rounds
+    .first()
+    .map(|round| {
+      round
+        .iter()
+        .take(sim.profile.blinds.len().min(players_init.len()))
+        .filter_map(|&(seat_id, _, m)| m.map(|m| (seat_id, m)))
+        .collect()
+    })
+    .unwrap_or(Vec::new());
+
+  let mut events = events;
+  events.extend(game_summary(&blinds, &showdown, &score));
+
+  Ok((
+    Log {
+      players_init: players_init.clone(),
+      events: events,
+      rounds: rounds,
+      table_cards: table_cards.clone(),
+    },
+    score,
+  ))
+}
+
+// Hands shown at showdown, the ones of the seats left in the main pot when more than one.
+pub fn showdown_hands(
+  players_init: &Vec<(SeatId, Money, Vec<Card>)>,
+  score: &Score,
+) -> Vec<(SeatId, Vec<Card>)> {
+  match score.pots.first() {
+    Some(pot) if pot.seats.len() > 1 => players_init
+      .iter()
+      .filter(|&&(seat_id, _, _)| pot.seats.contains(&seat_id))
+      .map(|&(seat_id, _, ref cards)| (seat_id, cards.clone()))
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+// Summary closing the log of a game, the blinds posted, the hands shown and the pot awards.
+pub fn game_summary(
+  blinds: &[(SeatId, Money)],
+  showdown: &[(SeatId, Vec<Card>)],
+  score: &Score,
+) -> Vec<Event> {
+  let mut events: Vec<Event> = blinds
+    .iter()
+    .map(|&(seat_id, amount)| Event::Blind { seat_id: seat_id, amount: amount })
+    .collect();
+
+  if !showdown.is_empty() {
+    events.push(Event::Showdown { hands: showdown.to_vec() });
+  }
+
+  for pot in score.pots.iter() {
+    for &(seat_id, amount) in pot.awards.iter() {
+      events.push(Event::Award { seat_id: seat_id, amount: amount });
+    }
+  }
+
+  events
+}
+
+// Shuffle the deck and deal private cards to players, along with the table cards of all rounds.
+pub fn table_deal<R: Rng>(
+  rng: &mut R,
+  sim: &Sim,
+  player_funds_init: &Vec<(SeatId, Money)>,
+) -> (Vec<(SeatId, Money, Vec<Card>)>, Vec<Card>) {
+  let mut players_init = Vec::new();
+
+  let mut cards: Vec<&Card> = sim.profile.deck.iter().collect();
+  rng.shuffle(&mut cards);
+
+  let private_cards_size = sim.profile.rounds[0];
+
+  for &(seat_id, m) in player_funds_init {
+    let cs: Vec<&Card> = cards.drain(0..private_cards_size).collect();
+    players_init.push((seat_id, m, cs.iter().map(|&c| c.clone()).collect()));
+  }
+
+  let table_cards_size = sim.profile.rounds.iter().sum::<usize>() - private_cards_size;
+  let table_cards: Vec<Card> = cards.iter().take(table_cards_size).map(|&c| c.clone()).collect();
+
+  (players_init, table_cards)
+}
+
+// Compute the final score of a game given the result of `table_game_simulate_`.
+pub fn table_score(
+  sim: &Sim,
+  evaluator: &Eval,
+  players_init: &Vec<(SeatId, Money, Vec<Card>)>,
+  table_cards: &[Card],
+  player_first: SeatId,
+  result: Option<(Vec<Money>, Vec<Money>, Vec<State>)>,
+) -> Score {
+  use rounds;
+
+  match result {
     None =>
     // Dead game.
     {
       Score {
         winners: Vec::new(),
         winners_score: 0.,
-        player_funds: player_funds_init.clone(),
+        player_funds: players_init.iter().map(|&(seat_id, m, _)| (seat_id, m)).collect(),
         pot: Money::zero(),
+        pots: Vec::new(),
       }
     }
     Some((mut player_funds, player_pots, player_states)) => {
-      let (_, ref winners, winners_score) = game_finish(evaluator, &table_cards, &player_states);
+      let (finalists, ref winners, winners_score) =
+        game_finish(evaluator, table_cards, &player_states);
       let pot = Money::from_u32(player_pots.iter().map(|&p| p.unpack()).sum::<u32>());
 
-      funds_update(sim, &mut player_funds, &player_pots, winners);
+      let finalists: Vec<(SeatId, f32)> =
+        finalists.iter().map(|&(seat_id, _, _, score)| (seat_id, score)).collect();
+      let dealer = rounds::seat_previous(sim.profile.players, player_first);
+
+      let pots =
+        funds_update(sim, &mut player_funds, &player_pots, &player_states, &finalists, dealer);
 
       // Extract updated funds
       let player_funds_final = players_init
@@ -349,19 +537,10 @@ pub fn table_game_simulate<E, R: Rng>(
         winners_score: winners_score,
         player_funds: player_funds_final,
         pot: pot,
+        pots: pots,
       }
     }
-  };
-
-  Ok((
-    Log {
-      players_init: players_init.clone(),
-      events: events,
-      rounds: rounds,
-      table_cards: table_cards.clone(),
-    },
-    score,
-  ))
+  }
 }
 
 pub fn table_game_simulate_<E, PT>(
@@ -381,6 +560,7 @@ where
   use anna_model::profile::Limit;
   use rounds;
   use std::cmp::min;
+  use std::time::{Duration, Instant};
   use Act;
   use Event;
 
@@ -415,8 +595,10 @@ where
   let mut table_target_raise: Money = blind_biggest; // - amount by which the target was raised
 
   let ref mut events: Vec<Event> = Vec::new();
+  let mut timeouts: Vec<(usize, SeatId)> = Vec::new(); // Logged only, before the event at.
 
   let mut round_raises: usize = 0; // Count of raises for the current round
+  let mut player_clocks: Vec<Duration> = vec![Duration::from_secs(0); player_funds.len()];
   let mut rounds: Vec<Vec<(usize, Action, Option<Money>)>> = Vec::new();
   let ref mut rounds_buffer: Vec<(usize, Action, Option<Money>)> = Vec::new();
 
@@ -454,8 +636,10 @@ where
   while running {
     if action_id == 0 {
       // Initialize rounds
-      let ev_table = round_start(evaluator, players_table, round_id, table_target)
-        .map_err(|e| (game_abort(&rounds, &rounds_buffer), events.clone(), Error::Play(e)))?;
+      let ev_table =
+        round_start(evaluator, players_table, round_id, table_target).map_err(|e| {
+          (game_abort(&rounds, &rounds_buffer), events_log(events, &timeouts), Error::Play(e))
+        })?;
       events.push(ev_table);
     }
 
@@ -473,7 +657,7 @@ where
           .rev()
           .take_while(|&a| match a {
             &Event::Play(Act { seat_id, .. }) => seat_id != active,
-            &Event::Table { .. } => true,
+            _ => true,
           })
           .count();
 
@@ -489,17 +673,67 @@ where
       Some(Limit { caps, .. }) => round_raises < caps,
     };
 
-    let action_i = players_table
-      .play(
-        round_id,
-        table_target,
-        if action_raisable { Some(table_target_raise) } else { None },
-        &player_pots,
-        active,
-        player_funds[active],
-        &events_player,
-      )
-      .map_err(|e| (game_abort(&rounds, &rounds_buffer), events.clone(), Error::Play(e)))?;
+    players_table.audit_decision(&TableView {
+      round_id: round_id,
+      seat_id: active,
+      raisable: action_raisable,
+      player_funds: &player_funds,
+      player_pots: &player_pots,
+      player_states: &player_states,
+      table_target: table_target,
+      table_target_raise: table_target_raise,
+      table_target_by: table_target_by,
+    });
+
+    let clock_spent = sim.budget.map_or(false, |budget| player_clocks[active] >= budget.game);
+
+    let action_played = if clock_spent {
+      None
+    } else {
+      let decision_start = Instant::now();
+
+      // Within both the decision and the game budgets.
+      let deadline = sim
+        .budget
+        .map(|budget| decision_start + min(budget.decision, budget.game - player_clocks[active]));
+      players_table.deadline(active, deadline);
+
+      let action_i = players_table
+        .play(
+          round_id,
+          table_target,
+          if action_raisable { Some(table_target_raise) } else { None },
+          &player_pots,
+          active,
+          player_funds[active],
+          &events_player,
+        )
+        .map_err(|e| {
+          (game_abort(&rounds, &rounds_buffer), events_log(events, &timeouts), Error::Play(e))
+        })?;
+
+      let decision_time = decision_start.elapsed();
+      player_clocks[active] += decision_time;
+
+      match sim.budget {
+        Some(budget) if decision_time > budget.decision || player_clocks[active] > budget.game => {
+          None
+        }
+        _ => Some(action_i),
+      }
+    };
+
+    // Out of time, check or fold for the player.
+    let action_i = match action_played {
+      Some(action_i) => action_i,
+      None => {
+        warn!("Player {} ran out of time, {:?} spent in game.", active, player_clocks[active]);
+        timeouts.push((events.len(), active));
+
+        let action = if player_pots[active] == table_target { Action::Call } else { Action::Fold };
+        sim.action_class.apply(blind_biggest, player_funds[active], action, Money::zero())
+      }
+    };
 
     // Apply action and record event
     let (action, money_opt, _) = table_run(
@@ -514,7 +748,22 @@ where
       active,
       action_i,
     )
-    .map_err(|e| (game_abort(&rounds, &rounds_buffer), events.clone(), e))?;
+    .map_err(|e| (game_abort(&rounds, &rounds_buffer), events_log(events, &timeouts), e))?;
+
+    players_table.audit_action(
+      &TableView {
+        round_id: round_id,
+        seat_id: active,
+        raisable: action_raisable,
+        player_funds: &player_funds,
+        player_pots: &player_pots,
+        player_states: &player_states,
+        table_target: table_target,
+        table_target_raise: table_target_raise,
+        table_target_by: table_target_by,
+      },
+      action_i,
+    );
 
     let event = Event::Play(Act { seat_id: active, action: action_i });
 
@@ -523,7 +772,11 @@ where
     rounds_buffer.push((active, action, money_opt));
 
     if action == Action::Raise {
-      assert!(action_raisable);
+      if !action_raisable {
+        let error =
+          Error::Sementic(format!("Can not `Raise` when raises are capped. player {}", active));
+        return Err((game_abort(&rounds, &rounds_buffer), events_log(events, &timeouts), error));
+      }
       round_raises += 1
     }
 
@@ -539,7 +792,9 @@ where
       dealer,
       round_id,
       active,
-    ) {
+    )
+    .map_err(|e| (game_abort(&rounds, &rounds_buffer), events_log(events, &timeouts), e))?
+    {
       Err(None) => {
         running = false;
       }
@@ -564,15 +819,31 @@ where
   Ok(if table_target_by.is_none() {
     // Dead game, no call/raise was made.
     for &(ref act, m) in blinds_actions.iter() {
-      rounds::pledge_unapply(&mut player_funds, &mut player_pots, act.seat_id, m);
+      if let Err(error) =
+        rounds::pledge_unapply(&mut player_funds, &mut player_pots, act.seat_id, m)
+      {
+        return Err((rounds, events_log(events, &timeouts), error));
+      }
     }
-    (rounds, events.clone(), None)
+    (rounds, events_log(events, &timeouts), None)
   } else {
-    (rounds, events.clone(), Some((player_funds, player_pots, player_states)))
+    (rounds, events_log(events, &timeouts), Some((player_funds, player_pots, player_states)))
   })
 }
 
-pub fn table_next(
+// Events of the log, the timeouts preceding the default actions played.
+fn events_log(events: &[Event], timeouts: &[(usize, SeatId)]) -> Vec<Event> {
+  let mut xs = Vec::with_capacity(events.len() + timeouts.len());
+  for (i, event) in events.iter().enumerate() {
+    for &(_, seat_id) in timeouts.iter().filter(|&&(j, _)| j == i) {
+      xs.push(Event::Timeout { seat_id: seat_id });
+    }
+    xs.push(event.clone());
+  }
+  xs
+}
+
+pub fn table_next<E>(
   sim: &Sim,
   player_pots: &[Money],
   player_states: &[State],
@@ -582,7 +853,7 @@ pub fn table_next(
   dealer: usize,
   round_id: usize,
   seat_id: usize,
-) -> Result<usize, Option<usize>> {
+) -> Result<Result<usize, Option<usize>>, Error<E>> {
   use rounds::{player_actives, seat_next_active};
 
   let player_actives: Vec<usize> = player_actives(&player_states);
@@ -590,26 +861,34 @@ pub fn table_next(
   if player_actives.len() == 0
     || (player_actives.len() == 1 && (*table_target - player_pots[player_actives[0]]) <= 0)
   {
-    Err(None)
+    Ok(Err(None))
   } else {
     // Next action
-    let seat_id_next = seat_next_active(sim.profile.players, &player_actives, seat_id).unwrap();
+    let seat_id_next = seat_next_active(sim.profile.players, &player_actives, seat_id)
+      .ok_or(Error::NoActiveSeat { seat_id: seat_id })?;
     if Some(seat_id_next) == *table_target_by {
-      assert!(player_pots[seat_id_next] == *table_target);
+      if player_pots[seat_id_next] != *table_target {
+        return Err(Error::PotOffTarget {
+          seat_id: seat_id_next,
+          pot: player_pots[seat_id_next],
+          target: *table_target,
+        });
+      }
 
       // We reached the end of the round, no more betting was done.
       if round_id == (sim.profile.rounds.len() - 1) {
-        Err(None)
+        Ok(Err(None))
       } else {
-        let seat_id_init = seat_next_active(sim.profile.players, &player_actives, dealer).unwrap();
+        let seat_id_init = seat_next_active(sim.profile.players, &player_actives, dealer)
+          .ok_or(Error::NoActiveSeat { seat_id: dealer })?;
 
         *table_target_raise = sim.blind_biggest;
         *table_target_by = Some(seat_id_init);
 
-        Err(Some(seat_id_init))
+        Ok(Err(Some(seat_id_init)))
       }
     } else {
-      Ok(seat_id_next)
+      Ok(Ok(seat_id_next))
     }
   }
 }
@@ -652,6 +931,10 @@ pub fn table_run<E>(
 ) -> Result<(Action, Option<Money>, Option<Money>), Error<E>> {
   use rounds;
 
+  if action_i as usize >= sim.action_class.size() {
+    return Err(Error::InvalidAction { seat_id: seat_id, action: action_i });
+  }
+
   let (action, money_opt) = sim
     .action_class
     .unapply(
@@ -690,7 +973,10 @@ pub fn table_run<E>(
     }
   } else {
     // Call/Raise
-    let money = money_opt.expect("Impossible to Call/Raise without money.");
+    let money = money_opt.ok_or(Error::Sementic(format!(
+      "Can not `Call`/`Raise` without money. player {}",
+      seat_id
+    )))?;
 
     if action == Action::Call && (*table_target - player_pots[seat_id]) <= 0 {
       return Err(Error::Sementic(format!(
@@ -704,9 +990,7 @@ pub fn table_run<E>(
       // AllIn
       let state = match player_states[seat_id] {
         State::Play { ref cards, .. } => State::Play { cards: cards.clone(), all_in: true },
-        _ => {
-          panic!("Can not `AllIn` on a non active player {}", seat_id);
-        }
+        _ => return Err(Error::InactiveSeat { seat_id: seat_id }),
       };
       player_states[seat_id] = state;
     }
@@ -725,25 +1009,28 @@ pub fn table_run<E>(
         // Apply "Full Bet" rule during AllIns.
         // http://www.dominolistings.com/rules-for-domino-all-in-situations-domino-side-pot-calculator
         if raise > 0 {
-          assert!((raise as u32) < table_target_raise.unpack());
+          if raise as u32 >= table_target_raise.unpack() {
+            return Err(Error::Sementic(format!(
+              "Can not `Call` all-in above a full raise. player {}",
+              seat_id
+            )));
+          }
+
+          let unpledge = Money::from_u32(raise as u32);
           money_norm = Some(Money::from_u32(money.unpack() - raise as u32));
-          rounds::pledge_unapply(
-            player_funds,
-            player_pots,
-            seat_id,
-            Money::from_i32(raise).unwrap(),
-          );
+          rounds::pledge_unapply(player_funds, player_pots, seat_id, unpledge)?;
         }
-      } else {
-        assert!(raise == 0);
+      } else if raise != 0 {
+        return Err(Error::Sementic(format!(
+          "Can not `Call` off target. player {}, raise {}",
+          seat_id, raise
+        )));
       }
 
       let target_set = match *table_target_by {
         Some(table_target_by) => match player_states[table_target_by] {
           State::Play { all_in, .. } => all_in,
-          _ => {
-            panic!("Can not have a non active player ({}) as table_target.", table_target_by);
-          }
+          _ => return Err(Error::InactiveSeat { seat_id: table_target_by }),
         },
         None => true,
       };
@@ -752,9 +1039,15 @@ pub fn table_run<E>(
         *table_target_by = Some(seat_id);
       };
     } else {
-      assert!(raise > 0 && raise as u32 >= table_target_raise.unpack());
+      if raise <= 0 || (raise as u32) < table_target_raise.unpack() {
+        return Err(Error::InvalidRaise {
+          seat_id: seat_id,
+          raise: raise,
+          raise_min: *table_target_raise,
+        });
+      }
 
-      *table_target_raise = Money::from_i32(raise).unwrap();
+      *table_target_raise = Money::from_u32(raise as u32);
       *table_target = player_pots[seat_id];
       *table_target_by = Some(seat_id);
     }
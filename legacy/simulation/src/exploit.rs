@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, Money};
+use game::{Error, GameState};
+use players::Players;
+use tree::{Node, NodeId, Tree};
+use SeatId;
+use Sim;
+
+// Action probabilities by information set, indexed by action class.
+pub type Strategy = HashMap<String, Vec<f32>>;
+
+#[derive(Clone, Debug)]
+pub struct Exploitability {
+  // Expected payoff of each seat when all play the strategy.
+  pub values: Vec<f32>,
+  // Expected payoff of each seat best responding to the others.
+  pub best_responses: Vec<f32>,
+  // Mean gain of a best response, in milli big blinds per hand.
+  pub mbb: f32,
+}
+
+/** Exploitability of any policy on a small profile (Kuhn, Leduc).
+ **
+ ** The policy is queried once per information set with a state where it has to act.
+ **/
+pub fn exploitability<F>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<Money>,
+  player_first: SeatId,
+  policy: F,
+) -> Result<Exploitability, Error>
+where
+  F: FnMut(&GameState) -> Vec<f32>,
+{
+  let tree = Tree::build(sim, evaluator, player_funds, player_first)?;
+  let strategy = strategy_collect(sim, evaluator, player_funds, player_first, policy)?;
+  Ok(exploitability_tree(&tree, &strategy, sim.blind_biggest))
+}
+
+/** Action probabilities of a player at a state where it has to act, as given by `Players::policy`
+ ** once the player is dealt the hand of the seat. Used as the policy of `exploitability` to measure
+ ** the strategy the player actually plays.
+ **/
+pub fn players_policy<E, P: Players<E>>(players: &mut P, state: &GameState) -> Vec<f32> {
+  let seat_id = state.current_player().expect("No player to act.");
+
+  let player_funds: Vec<Money> = state.players_init.iter().map(|&(_, m, _)| m).collect();
+  let playing_hands: Vec<(SeatId, Vec<Card>)> = state
+    .players_init
+    .iter()
+    .filter(|&&(s, _, _)| s == seat_id)
+    .map(|&(s, _, ref cards)| (s, cards.clone()))
+    .collect();
+
+  players.init(&state.sim.profile.blinds, state.player_first, &player_funds, &playing_hands);
+
+  players.policy(
+    state.round_id,
+    state.table_target,
+    state.table_target_raise(),
+    &state.player_pots,
+    seat_id,
+    state.player_funds[seat_id],
+    &state.events,
+  )
+}
+
+pub fn exploitability_tree(
+  tree: &Tree,
+  strategy: &Strategy,
+  blind_biggest: Money,
+) -> Exploitability {
+  let values = strategy_values(tree, strategy);
+  let best_responses: Vec<f32> =
+    (0..tree.players).map(|seat_id| best_response(tree, strategy, seat_id)).collect();
+
+  let gain: f32 = best_responses.iter().zip(values.iter()).map(|(br, v)| br - v).sum();
+  let mbb = gain / tree.players as f32 / blind_biggest.unpack() as f32 * 1000.;
+
+  Exploitability { values: values, best_responses: best_responses, mbb: mbb }
+}
+
+pub fn strategy_collect<F>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<Money>,
+  player_first: SeatId,
+  mut policy: F,
+) -> Result<Strategy, Error>
+where
+  F: FnMut(&GameState) -> Vec<f32>,
+{
+  use tree::tree_deals;
+
+  let mut strategy = Strategy::new();
+
+  for hands in tree_deals(sim, &sim.profile.deck, sim.profile.players) {
+    let players_init: Vec<(SeatId, Money, Vec<Card>)> = hands
+      .into_iter()
+      .enumerate()
+      .map(|(seat_id, cards)| (seat_id, player_funds[seat_id], cards))
+      .collect();
+
+    let state = GameState::new(sim, evaluator, &players_init, &Vec::new(), player_first)?;
+    strategy_collect_(&mut strategy, &state, &mut policy)?;
+  }
+
+  Ok(strategy)
+}
+
+fn strategy_collect_<F>(
+  strategy: &mut Strategy,
+  state: &GameState,
+  policy: &mut F,
+) -> Result<(), Error>
+where
+  F: FnMut(&GameState) -> Vec<f32>,
+{
+  if state.is_terminal() {
+    return Ok(());
+  }
+
+  if state.is_chance() {
+    for cards in state.chance_outcomes() {
+      strategy_collect_(strategy, &state.apply_chance(&cards)?, policy)?;
+    }
+    return Ok(());
+  }
+
+  let key = state.infoset_key(state.current_player().unwrap());
+  if !strategy.contains_key(&key) {
+    let probs = policy(state);
+    strategy.insert(key, probs);
+  }
+
+  for action_i in state.legal_actions() {
+    strategy_collect_(strategy, &state.apply(action_i)?, policy)?;
+  }
+
+  Ok(())
+}
+
+// Probabilities of the legal actions of a decision node, uniform when the strategy is unknown.
+pub fn strategy_probs(
+  strategy: &Strategy,
+  infoset: &String,
+  actions: &[(u8, NodeId)],
+) -> Vec<f32> {
+  let probs: Vec<f32> = match strategy.get(infoset) {
+    None => vec![1.; actions.len()],
+    Some(xs) => actions.iter().map(|&(a, _)| *xs.get(a as usize).unwrap_or(&0.)).collect(),
+  };
+
+  let total: f32 = probs.iter().sum();
+  if total > 0. {
+    probs.iter().map(|p| p / total).collect()
+  } else {
+    vec![1. / actions.len() as f32; actions.len()]
+  }
+}
+
+// Expected payoffs of every seat when all play the strategy.
+pub fn strategy_values(tree: &Tree, strategy: &Strategy) -> Vec<f32> {
+  // Children are always stored before their parent.
+  let mut values: Vec<Vec<f32>> = Vec::with_capacity(tree.nodes.len());
+
+  for node in tree.nodes.iter() {
+    let value = match node {
+      &Node::Terminal { ref payoffs } => {
+        let mut xs = vec![0.; tree.players];
+        for &(seat_id, m) in payoffs {
+          xs[seat_id] = m as f32;
+        }
+        xs
+      }
+      &Node::Chance { ref outcomes } => {
+        let mut xs = vec![0.; tree.players];
+        for &(_, p, child) in outcomes {
+          for (x, v) in xs.iter_mut().zip(values[child].iter()) {
+            *x += p * v;
+          }
+        }
+        xs
+      }
+      &Node::Decision { ref infoset, ref actions, .. } => {
+        let probs = strategy_probs(strategy, infoset, actions);
+        let mut xs = vec![0.; tree.players];
+        for (&(_, child), p) in actions.iter().zip(probs) {
+          for (x, v) in xs.iter_mut().zip(values[child].iter()) {
+            *x += p * v;
+          }
+        }
+        xs
+      }
+    };
+    values.push(value);
+  }
+
+  values[tree.root].clone()
+}
+
+// Expected payoff of a seat playing a best response against the strategy of the others.
+pub fn best_response(tree: &Tree, strategy: &Strategy, seat_id: SeatId) -> f32 {
+  // Reach probability of every node due to chance and the other seats.
+  let mut reach = vec![0.; tree.nodes.len()];
+  reach[tree.root] = 1.;
+
+  for node_id in (0..tree.nodes.len()).rev() {
+    let r = reach[node_id];
+    match &tree.nodes[node_id] {
+      &Node::Terminal { .. } => {}
+      &Node::Chance { ref outcomes } => {
+        for &(_, p, child) in outcomes {
+          reach[child] = r * p;
+        }
+      }
+      &Node::Decision { seat_id: s, ref infoset, ref actions } => {
+        let probs = strategy_probs(strategy, infoset, actions);
+        for (&(_, child), p) in actions.iter().zip(probs) {
+          reach[child] = if s == seat_id { r } else { r * p };
+        }
+      }
+    }
+  }
+
+  let mut br = BestResponse {
+    tree: tree,
+    strategy: strategy,
+    seat_id: seat_id,
+    reach: reach,
+    infosets: tree.infosets(),
+    choices: HashMap::new(),
+    values: vec![None; tree.nodes.len()],
+  };
+
+  br.value(tree.root)
+}
+
+struct BestResponse<'a> {
+  tree: &'a Tree,
+  strategy: &'a Strategy,
+  seat_id: SeatId,
+  reach: Vec<f32>,
+  infosets: HashMap<String, Vec<NodeId>>,
+  choices: HashMap<String, usize>,
+  values: Vec<Option<f32>>,
+}
+
+impl<'a> BestResponse<'a> {
+  fn value(&mut self, node_id: NodeId) -> f32 {
+    if let Some(v) = self.values[node_id] {
+      return v;
+    }
+
+    let tree = self.tree;
+
+    let v = match &tree.nodes[node_id] {
+      &Node::Terminal { ref payoffs } => {
+        payoffs.iter().find(|&&(s, _)| s == self.seat_id).map(|&(_, m)| m as f32).unwrap_or(0.)
+      }
+      &Node::Chance { ref outcomes } => {
+        outcomes.iter().map(|&(_, p, child)| p * self.value(child)).sum()
+      }
+      &Node::Decision { seat_id, ref infoset, ref actions } => {
+        if seat_id == self.seat_id {
+          let choice = self.choice(infoset);
+          self.value(actions[choice].1)
+        } else {
+          let probs = strategy_probs(self.strategy, infoset, actions);
+          actions.iter().zip(probs).map(|(&(_, child), p)| p * self.value(child)).sum()
+        }
+      }
+    };
+
+    self.values[node_id] = Some(v);
+    v
+  }
+
+  // Action maximizing the counterfactual value over every node of an information set.
+  fn choice(&mut self, infoset: &String) -> usize {
+    if let Some(&choice) = self.choices.get(infoset) {
+      return choice;
+    }
+
+    let node_ids = self.infosets[infoset].clone();
+    let mut scores: Vec<f32> = Vec::new();
+
+    for node_id in node_ids {
+      let actions = match &self.tree.nodes[node_id] {
+        &Node::Decision { ref actions, .. } => actions.clone(),
+        _ => unreachable!(),
+      };
+
+      scores.resize(actions.len(), 0.);
+      for (k, &(_, child)) in actions.iter().enumerate() {
+        scores[k] += self.reach[node_id] * self.value(child);
+      }
+    }
+
+    let mut choice = 0;
+    for (k, &score) in scores.iter().enumerate() {
+      if score > scores[choice] {
+        choice = k;
+      }
+    }
+
+    self.choices.insert(infoset.clone(), choice);
+    choice
+  }
+}
@@ -0,0 +1,525 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, profile::Limit, Action, Money};
+use engine::{self, Rounds, Score};
+use Act;
+use Event;
+use SeatId;
+use Sim;
+use State;
+
+pub type Error = engine::Error<()>;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Step {
+  Play(u8),
+  Deal(Vec<Card>),
+}
+
+/** Game State
+ **
+ ** Step-wise counterpart of `engine::table_game_simulate_`, a value which can be queried for the
+ ** legal actions of the current player, advanced with `apply` and rolled back with `undo`.
+ **
+ ** Table cards which are not known when the state is created are dealt with `apply_chance` once
+ ** the corresponding round is reached. When the players left are all-in, the remaining rounds are
+ ** dealt before the state becomes terminal, payoffs being scored on the whole table.
+ **/
+#[derive(Clone)]
+pub struct GameState<'a> {
+  pub sim: &'a Sim<'a>,
+  pub evaluator: &'a Eval,
+
+  pub players_init: Vec<(SeatId, Money, Vec<Card>)>,
+  pub player_first: SeatId,
+  pub table_cards: Vec<Card>,
+
+  pub player_funds: Vec<Money>,
+  pub player_pots: Vec<Money>,
+  pub player_states: Vec<State>,
+
+  pub table_target: Money,
+  pub table_target_raise: Money,
+  pub table_target_by: Option<SeatId>,
+
+  pub round_id: usize,
+  pub round_raises: usize,
+  pub active: SeatId,
+
+  pub events: Vec<Event>,
+  pub rounds: Rounds,
+
+  steps: Vec<Step>,
+  table_cards_init: usize,
+  dealer: SeatId,
+  runout: bool,
+  terminal: bool,
+}
+
+impl<'a> GameState<'a> {
+  pub fn new(
+    sim: &'a Sim<'a>,
+    evaluator: &'a Eval,
+    players_init: &Vec<(SeatId, Money, Vec<Card>)>,
+    table_cards: &Vec<Card>,
+    player_first: SeatId,
+  ) -> Result<GameState<'a>, Error> {
+    use engine::table_players_init;
+    use rounds;
+
+    let blind_biggest = sim.blind_biggest;
+    let blinds_ante: bool = sim.profile.blinds.iter().all(|&m| m == blind_biggest);
+
+    let (mut player_funds, mut player_pots, player_states) = table_players_init(sim, players_init)?;
+
+    let (active, blinds_actions) =
+      rounds::blinds_apply(sim, player_first, &mut player_funds, &mut player_pots, &player_states);
+
+    let mut events = Vec::new();
+    let mut rounds_buffer = Vec::new();
+
+    for &(ref act, m) in blinds_actions.iter() {
+      rounds_buffer.push((act.seat_id, Action::Raise, Some(m)));
+      if !blinds_ante {
+        events.push(Event::Play(act.clone()));
+      }
+    }
+
+    Ok(GameState {
+      sim: sim,
+      evaluator: evaluator,
+      players_init: players_init.clone(),
+      player_first: player_first,
+      table_cards: table_cards.clone(),
+      player_funds: player_funds,
+      player_pots: player_pots,
+      player_states: player_states,
+      table_target: blind_biggest,
+      table_target_raise: blind_biggest,
+      table_target_by: if blinds_ante { Some(active) } else { None },
+      round_id: 0,
+      round_raises: 0,
+      active: active,
+      events: events,
+      rounds: vec![rounds_buffer],
+      steps: Vec::new(),
+      table_cards_init: table_cards.len(),
+      dealer: rounds::seat_previous(sim.profile.players, player_first),
+      runout: false,
+      terminal: false,
+    })
+  }
+
+  // Deal a new game using the simulation deck.
+  pub fn deal<R: Rng>(
+    rng: &mut R,
+    sim: &'a Sim<'a>,
+    evaluator: &'a Eval,
+    player_funds_init: &Vec<(SeatId, Money)>,
+    player_first: SeatId,
+  ) -> Result<GameState<'a>, Error> {
+    let (players_init, table_cards) = engine::table_deal(rng, sim, player_funds_init);
+    GameState::new(sim, evaluator, &players_init, &table_cards, player_first)
+  }
+
+  pub fn is_terminal(&self) -> bool {
+    self.terminal
+  }
+
+  // Waiting for the table cards of the current round.
+  pub fn is_chance(&self) -> bool {
+    !self.terminal && self.table_cards.len() < self.table_cards_size(self.round_id)
+  }
+
+  pub fn current_player(&self) -> Option<SeatId> {
+    if self.terminal || self.is_chance() {
+      None
+    } else {
+      Some(self.active)
+    }
+  }
+
+  // Actions played since the game started, blinds excluded.
+  pub fn actions(&self) -> Vec<u8> {
+    self
+      .steps
+      .iter()
+      .filter_map(|step| match step {
+        &Step::Play(action_i) => Some(action_i),
+        &Step::Deal(_) => None,
+      })
+      .collect()
+  }
+
+  pub fn steps(&self) -> &[Step] {
+    &self.steps
+  }
+
+  // Count of table cards revealed up to a given round.
+  pub fn table_cards_size(&self, round_id: usize) -> usize {
+    self.sim.profile.rounds.iter().take(round_id + 1).skip(1).sum()
+  }
+
+  // Cards which are neither in a player hand nor on the table.
+  pub fn deck_remaining(&self) -> Vec<Card> {
+    self
+      .sim
+      .profile
+      .deck
+      .iter()
+      .filter(|card| {
+        !self.table_cards.contains(card)
+          && !self.players_init.iter().any(|&(_, _, ref cards)| cards.contains(card))
+      })
+      .cloned()
+      .collect()
+  }
+
+  // Every table cards combination which can be dealt at a chance state.
+  pub fn chance_outcomes(&self) -> Vec<Vec<Card>> {
+    use anna_utils::math::combinations;
+
+    if !self.is_chance() {
+      return Vec::new();
+    }
+
+    let deck = self.deck_remaining();
+    let size = self.sim.profile.rounds[self.round_id];
+
+    combinations(deck.len(), size).iter().map(|xs| xs.iter().map(|&i| deck[i]).collect()).collect()
+  }
+
+  pub fn apply_chance(&self, cards: &[Card]) -> Result<GameState<'a>, Error> {
+    let mut state = self.clone();
+    state.apply_chance_mut(cards)?;
+    Ok(state)
+  }
+
+  pub fn apply_chance_mut(&mut self, cards: &[Card]) -> Result<(), Error> {
+    if !self.is_chance() || cards.len() != self.sim.profile.rounds[self.round_id] {
+      return Err(Error::Sementic(format!("Can not deal {:?} in round {}", cards, self.round_id)));
+    }
+
+    self.table_cards.extend(cards.iter());
+    self.events.push(Event::Table { cards: cards.to_vec() });
+    self.steps.push(Step::Deal(cards.to_vec()));
+
+    if self.runout {
+      self.runout_mut();
+    }
+
+    Ok(())
+  }
+
+  // Move through the rounds left without any player to act, until table cards are to be dealt.
+  fn runout_mut(&mut self) -> () {
+    use rounds::round_cards;
+
+    while self.round_id + 1 < self.sim.profile.rounds.len() {
+      self.round_id += 1;
+      self.round_raises = 0;
+      self.rounds.push(Vec::new());
+
+      if self.is_chance() {
+        return;
+      }
+
+      let cards = round_cards(&self.sim.profile.rounds, &self.table_cards, self.round_id);
+      self.events.push(Event::Table { cards: cards });
+    }
+
+    self.terminal = true;
+  }
+
+  pub fn raisable(&self) -> bool {
+    match self.sim.profile.limit {
+      None => true,
+      Some(Limit { caps, .. }) => self.round_raises < caps,
+    }
+  }
+
+  // Raise amount to be given to players, `None` when the round is capped.
+  pub fn table_target_raise(&self) -> Option<Money> {
+    if self.raisable() {
+      Some(self.table_target_raise)
+    } else {
+      None
+    }
+  }
+
+  pub fn legal_mask(&self) -> HashSet<usize> {
+    if self.terminal || self.is_chance() {
+      HashSet::new()
+    } else {
+      self.sim.action_class.normalize(
+        self.sim.blind_biggest,
+        self.round_id,
+        self.table_target,
+        self.table_target_raise(),
+        self.player_funds[self.active],
+        self.player_pots[self.active],
+      )
+    }
+  }
+
+  pub fn legal_actions(&self) -> Vec<u8> {
+    let mask = self.legal_mask();
+    (0..self.sim.action_class.size()).filter(|i| mask.contains(i)).map(|i| i as u8).collect()
+  }
+
+  pub fn apply(&self, action_i: u8) -> Result<GameState<'a>, Error> {
+    let mut state = self.clone();
+    state.apply_mut(action_i)?;
+    Ok(state)
+  }
+
+  pub fn apply_mut(&mut self, action_i: u8) -> Result<(), Error> {
+    use engine::{table_next, table_run};
+
+    if self.terminal || self.is_chance() {
+      return Err(Error::Sementic(format!(
+        "Can not play on a terminal state. action {}",
+        action_i
+      )));
+    }
+
+    if self.sim.action_class.is_raise(action_i) && !self.raisable() {
+      return Err(Error::Sementic(format!(
+        "Can not `Raise` when the round is capped. player {}",
+        self.active
+      )));
+    }
+
+    let seat_id = self.active;
+
+    let (action, money_opt, _) = table_run(
+      self.sim,
+      self.round_id,
+      &mut self.player_funds,
+      &mut self.player_pots,
+      &mut self.player_states,
+      &mut self.table_target,
+      &mut self.table_target_raise,
+      &mut self.table_target_by,
+      seat_id,
+      action_i,
+    )?;
+
+    self.events.push(Event::Play(Act { seat_id: seat_id, action: action_i }));
+    self.rounds.last_mut().unwrap().push((seat_id, action, money_opt));
+    self.steps.push(Step::Play(action_i));
+
+    if action == Action::Raise {
+      self.round_raises += 1;
+    }
+
+    match table_next(
+      self.sim,
+      &self.player_pots,
+      &self.player_states,
+      &self.table_target,
+      &mut self.table_target_raise,
+      &mut self.table_target_by,
+      self.dealer,
+      self.round_id,
+      seat_id,
+    )? {
+      Err(None) => {
+        let finalists = self
+          .player_states
+          .iter()
+          .filter(|s| match s {
+            &&State::Play { .. } => true,
+            _ => false,
+          })
+          .count();
+
+        // Showdown of all-in players, once the table is complete.
+        if finalists > 1 {
+          self.runout = true;
+          self.runout_mut();
+        } else {
+          self.terminal = true;
+        }
+      }
+      Err(Some(next)) => {
+        use rounds::round_cards;
+
+        self.active = next;
+        self.round_id += 1;
+        self.round_raises = 0;
+        self.rounds.push(Vec::new());
+
+        if !self.is_chance() {
+          let cards = round_cards(&self.sim.profile.rounds, &self.table_cards, self.round_id);
+          self.events.push(Event::Table { cards: cards });
+        }
+      }
+      Ok(next) => {
+        self.active = next;
+      }
+    }
+
+    Ok(())
+  }
+
+  // Rewind the last step by replaying the game from the deal.
+  pub fn undo(&self) -> Option<GameState<'a>> {
+    let (_, steps) = self.steps.split_last()?;
+
+    let mut state = GameState::new(
+      self.sim,
+      self.evaluator,
+      &self.players_init,
+      &self.table_cards[..self.table_cards_init].to_vec(),
+      self.player_first,
+    )
+    .ok()?;
+
+    for step in steps {
+      match step {
+        &Step::Play(action_i) => state.apply_mut(action_i).ok()?,
+        &Step::Deal(ref cards) => state.apply_chance_mut(cards).ok()?,
+      }
+    }
+
+    Some(state)
+  }
+
+  pub fn infoset_key(&self, seat_id: SeatId) -> String {
+    let cards: Vec<Card> = self
+      .players_init
+      .iter()
+      .filter(|&&(s, _, _)| s == seat_id)
+      .flat_map(|&(_, _, ref cards)| cards.clone())
+      .collect();
+
+    infoset_key(self.sim.profile.players, self.player_first, seat_id, &cards, &self.events)
+  }
+
+  pub fn score(&self) -> Option<Score> {
+    if !self.terminal {
+      return None;
+    }
+
+    // Dead game, no call/raise was made.
+    let result = if self.table_target_by.is_none() {
+      None
+    } else {
+      Some((self.player_funds.clone(), self.player_pots.clone(), self.player_states.clone()))
+    };
+
+    Some(engine::table_score(
+      self.sim,
+      self.evaluator,
+      &self.players_init,
+      &self.table_cards,
+      self.player_first,
+      result,
+    ))
+  }
+
+  // Money won (or lost) by each player, available once the game is over.
+  pub fn payoffs(&self) -> Option<Vec<(SeatId, i32)>> {
+    self.score().map(|score| {
+      self
+        .players_init
+        .iter()
+        .map(|&(seat_id, fund_init, _)| {
+          let &(_, fund_final) = score.player_funds.iter().find(|&&(s, _)| s == seat_id).unwrap();
+          (seat_id, fund_final - fund_init)
+        })
+        .collect()
+    })
+  }
+}
+
+/** Key identifying the information set of a player.
+ **
+ ** Made of its position from the first player, its private cards, the table cards and the public
+ ** actions, rounds being separated by `/`. Events are the ones of the game so far, as given to
+ ** `Players::play` once concatenated.
+ **/
+pub fn infoset_key(
+  players: usize,
+  player_first: SeatId,
+  seat_id: SeatId,
+  cards: &[Card],
+  events: &[Event],
+) -> String {
+  let mut key = format!("{}|", (seat_id + players - player_first) % players);
+
+  for card in cards {
+    key.push_str(&card.to_string());
+  }
+
+  key.push('|');
+  for event in events.iter() {
+    if let &Event::Table { ref cards } = event {
+      for card in cards {
+        key.push_str(&card.to_string());
+      }
+    }
+  }
+
+  key.push('|');
+  key.push_str(&infoset_history(events));
+
+  key
+}
+
+// Card abstraction mapping a private hand and the table cards to a bucket.
+pub trait CardsAbstraction: Sync {
+  fn bucket(&self, hand: &[Card], board: &[Card]) -> usize;
+}
+
+impl<F> CardsAbstraction for F
+where
+  F: Fn(&[Card], &[Card]) -> usize + Sync,
+{
+  fn bucket(&self, hand: &[Card], board: &[Card]) -> usize {
+    self(hand, board)
+  }
+}
+
+// Same as `infoset_key`, the cards being replaced by their bucket.
+pub fn infoset_key_bucket(
+  players: usize,
+  player_first: SeatId,
+  seat_id: SeatId,
+  cards: &[Card],
+  events: &[Event],
+  abstraction: &CardsAbstraction,
+) -> String {
+  let mut board = Vec::new();
+  for event in events.iter() {
+    if let &Event::Table { ref cards } = event {
+      board.extend(cards.iter());
+    }
+  }
+
+  format!(
+    "{}|#{}|{}",
+    (seat_id + players - player_first) % players,
+    abstraction.bucket(cards, &board),
+    infoset_history(events)
+  )
+}
+
+// Public actions, rounds being separated by `/`.
+fn infoset_history(events: &[Event]) -> String {
+  let mut history = String::new();
+
+  for event in events.iter() {
+    match event {
+      &Event::Play(Act { action, .. }) => history.push_str(&action.to_string()),
+      &Event::Table { .. } => history.push('/'),
+      _ => {}
+    }
+  }
+
+  history
+}
@@ -0,0 +1,185 @@
+use rand::Rng;
+use serde_json;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::iter::FromIterator;
+use std::path::Path;
+
+use anna_eval::Eval;
+use anna_model::Money;
+use perf::{duplicate_rates, RateStats};
+use players::Players;
+use players_mul::PlayersMul2;
+use Sim;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rating {
+  pub elo: f32,
+  pub matches: usize,
+  pub hands: usize,
+}
+
+/** Elo ratings of league entries (networks, bots), keyed by name.
+ **
+ ** Persisted as JSON so that entries can be added to an existing league.
+ **/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ratings {
+  pub k: f32,
+  pub entries: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+  pub fn new(k: f32) -> Ratings {
+    Ratings { k: k, entries: BTreeMap::new() }
+  }
+
+  pub fn load(path: &Path) -> io::Result<Ratings> {
+    let file = File::open(path)?;
+    serde_json::from_reader(BufReader::new(file))
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  // Existing ratings, or a new league when the file is missing.
+  pub fn load_or_new(path: &Path, k: f32) -> io::Result<Ratings> {
+    if path.exists() {
+      Ratings::load(path)
+    } else {
+      Ok(Ratings::new(k))
+    }
+  }
+
+  pub fn save(&self, path: &Path) -> io::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), self)
+      .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+  }
+
+  // New entries start at 1500.
+  pub fn insert(&mut self, name: &str) -> () {
+    self.entries.entry(name.to_string()).or_insert(Rating { elo: 1500., matches: 0, hands: 0 });
+  }
+
+  pub fn elo(&self, name: &str) -> f32 {
+    self.entries.get(name).map(|r| r.elo).unwrap_or(1500.)
+  }
+
+  // Expected score of `a` against `b`.
+  pub fn expected(&self, a: &str, b: &str) -> f32 {
+    1. / (1. + 10f32.powf((self.elo(b) - self.elo(a)) / 400.))
+  }
+
+  // Update both entries with the score of `a` against `b`, 1 for a win, 0.5 for a draw.
+  pub fn update(&mut self, a: &str, b: &str, score: f32, hands: usize) -> () {
+    let delta = self.k * (score - self.expected(a, b));
+
+    self.insert(a);
+    self.insert(b);
+
+    for &(name, d) in [(a, delta), (b, -delta)].iter() {
+      let rating = self.entries.get_mut(name).unwrap();
+      rating.elo += d;
+      rating.matches += 1;
+      rating.hands += hands;
+    }
+  }
+
+  // Entries by decreasing rating.
+  pub fn table(&self) -> Vec<(String, Rating)> {
+    let mut xs: Vec<(String, Rating)> =
+      self.entries.iter().map(|(name, rating)| (name.clone(), rating.clone())).collect();
+    xs.sort_by(|&(_, ref a), &(_, ref b)| b.elo.partial_cmp(&a.elo).unwrap());
+    xs
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Schedule {
+  RoundRobin,     // Every pair once.
+  Sampled(usize), // Pairs drawn uniformly.
+}
+
+pub fn schedule<R: Rng>(
+  rng: &mut R,
+  names: &[String],
+  schedule: Schedule,
+) -> Vec<(String, String)> {
+  let n = names.len();
+
+  match schedule {
+    Schedule::RoundRobin => {
+      let mut pairs = Vec::new();
+      for i in 0..n {
+        for j in (i + 1)..n {
+          pairs.push((names[i].clone(), names[j].clone()));
+        }
+      }
+      pairs
+    }
+    Schedule::Sampled(_) if n < 2 => Vec::new(),
+    Schedule::Sampled(matches) => (0..matches)
+      .map(|_| {
+        let i = rng.gen_range(0, n);
+        let j = (i + rng.gen_range(1, n)) % n;
+        (names[i].clone(), names[j].clone())
+      })
+      .collect(),
+  }
+}
+
+// Score of a match from the rate of the first entry, a draw unless the interval excludes zero.
+pub fn match_score(stats: &RateStats) -> f32 {
+  let (low, high) = stats.interval();
+
+  if low > 0. {
+    1.
+  } else if high < 0. {
+    0.
+  } else {
+    0.5
+  }
+}
+
+/** League runner
+ **
+ ** Plays the scheduled matches over duplicate deals, the first entry of a match in seat 0 against
+ ** the second one in every other seat, and updates the ratings after each match. `players` builds
+ ** the players of an entry from its name.
+ **/
+pub fn league_run<'a, R: Rng, F>(
+  rng: &mut R,
+  sim: &Sim,
+  evaluator: &Eval,
+  fund: Money,
+  ratings: &mut Ratings,
+  matches: &[(String, String)],
+  hands: usize,
+  mut players: F,
+) -> Vec<(String, String, RateStats)>
+where
+  F: FnMut(&str) -> Box<Players<()> + 'a>,
+{
+  let seats = sim.profile.players;
+  let player_funds = (0..seats).map(|seat_id| (seat_id, fund)).collect();
+  let deals = (hands / seats).max(1);
+
+  let mut results = Vec::with_capacity(matches.len());
+
+  for &(ref a, ref b) in matches {
+    let ref mut table = PlayersMul2(
+      (players(a), HashSet::from_iter(vec![0])),
+      (players(b), HashSet::from_iter(1..seats)),
+    );
+
+    let rates = duplicate_rates(rng, sim, evaluator, &player_funds, deals, table);
+    let stats = RateStats::new(&rates.iter().map(|rates| rates[0]).collect());
+
+    ratings.update(a, b, match_score(&stats), deals * seats);
+    info!("League {} vs {}: {}", a, b, stats);
+
+    results.push((a.clone(), b.clone(), stats));
+  }
+
+  results
+}
@@ -2,32 +2,62 @@
 extern crate log;
 extern crate rand;
 extern crate rayon;
+extern crate serde;
+extern crate serde_json;
+#[macro_use]
+extern crate serde_derive;
 
 extern crate anna_eval;
 extern crate anna_model;
 extern crate anna_utils;
 
+pub mod acpc;
+pub mod audit;
+pub mod aivat;
+pub mod cfr;
 pub mod engine;
+pub mod exploit;
+pub mod game;
+pub mod league;
+pub mod mccfr;
+pub mod opponent_stats;
 pub mod perf;
 pub mod players;
 pub mod players_kuhn;
 pub mod players_lex;
 pub mod players_mul;
+pub mod players_recorder;
+pub mod players_remote;
+pub mod players_rules;
 pub mod players_sel;
 pub mod rounds;
+pub mod tree;
 
 use anna_model::{cards::Card, profile::Profile, ActionClass, Money};
+use std::time::Duration;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Act {
   pub seat_id: usize,
   pub action: u8,
 }
 
-#[derive(Clone, Debug)]
+/** Events of a game
+ **
+ ** `Play` and `Table` events make the history given to `Players::play`, blinds being posted as
+ ** raises unless all equal (ante). In the `Log` only, a `Timeout` precedes the default action
+ ** played for a seat which ran out of time, and the game closes with its summary: the blinds
+ ** posted, the hands shown at showdown and the pot awards. `Blind`, `Showdown` and `Award` events
+ ** are only found in the summary, the blinds of the history being its leading raises.
+ **/
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
   Play(Act),
   Table { cards: Vec<Card> },
+  Timeout { seat_id: SeatId },
+  Blind { seat_id: SeatId, amount: Money },
+  Showdown { hands: Vec<(SeatId, Vec<Card>)> },
+  Award { seat_id: SeatId, amount: Money },
 }
 
 pub type SeatId = usize;
@@ -42,6 +72,8 @@ pub struct Sim<'a> {
   //   - folding when at target
   //   - all-in which don't strictly match the bet
   pub strict: bool,
+  pub odd_chip: OddChip,
+  pub budget: Option<Budget>,
 }
 
 impl<'a> Sim<'a> {
@@ -51,6 +83,28 @@ impl<'a> Sim<'a> {
   }
 }
 
+// Who receives the chips left over when a pot can't be split evenly between its winners.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OddChip {
+  ButtonLeft, // First winner on the left of the button.
+  SuitLowest, // Winner holding the lowest card by suit.
+}
+
+/** Time allowed to each seat, for a single decision and for all its decisions of a game.
+ **
+ ** Deadlines are cooperative. The deadline of every decision is given to the players
+ ** (`Players::deadline`), remote players giving up waiting for their client at it, but a player
+ ** blocking inside `Players::play` is never interrupted: its action is only discarded once
+ ** returned. Either way the action is replaced by a check, or a fold when facing a bet, in strict
+ ** mode too, and a seat out of game budget is not asked anymore until the end of the game. The
+ ** game goes on, timeouts being reported in the log (`Event::Timeout`).
+ **/
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Budget {
+  pub decision: Duration,
+  pub game: Duration,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum State {
   Play { cards: Vec<Card>, all_in: bool },
@@ -0,0 +1,311 @@
+use rand::{Rng, SeedableRng, StdRng};
+use std::collections::HashMap;
+use std::path::Path;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, profile::Profile, Money};
+use anna_utils::bincode;
+use exploit::Strategy;
+use game::{CardsAbstraction, Error, GameState};
+use SeatId;
+use Sim;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Sampling {
+  External, // Every action of the updated seat, one sampled action for the others.
+  Outcome,  // A single sampled trajectory, exploring the updated seat actions.
+}
+
+// Regrets and strategy sums by information set, indexed by action class.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Tables {
+  pub iterations: usize,
+  pub regrets: HashMap<String, Vec<f32>>,
+  pub strategy_sum: HashMap<String, Vec<f32>>,
+}
+
+impl Tables {
+  pub fn load(path: &Path) -> Result<Tables, bincode::Error> {
+    bincode::deserialize_from_file(path)
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), bincode::Error> {
+    bincode::serialize_into_file(path, self)
+  }
+
+  pub fn strategy(&self) -> Strategy {
+    use cfr::strategy_average;
+    strategy_average(&self.strategy_sum)
+  }
+}
+
+/** Monte-Carlo CFR
+ **
+ ** Samples deals with the engine rules instead of walking a full tree, so it applies to profiles
+ ** too large for `cfr::Cfr`. Cards can be bucketed with a `CardsAbstraction`.
+ **
+ ** ref: http://mlanctot.info/files/papers/nips09mccfr.pdf
+ **/
+pub struct Mccfr<'a> {
+  pub sim: &'a Sim<'a>,
+  pub evaluator: &'a Eval,
+  pub abstraction: Option<&'a CardsAbstraction>,
+  pub player_funds: Vec<Money>,
+  pub sampling: Sampling,
+  pub exploration: f32, // Outcome sampling only.
+  pub tables: Tables,
+}
+
+impl<'a> Mccfr<'a> {
+  pub fn new(
+    sim: &'a Sim<'a>,
+    evaluator: &'a Eval,
+    abstraction: Option<&'a CardsAbstraction>,
+    player_funds: &Vec<Money>,
+    sampling: Sampling,
+  ) -> Mccfr<'a> {
+    Mccfr {
+      sim: sim,
+      evaluator: evaluator,
+      abstraction: abstraction,
+      player_funds: player_funds.clone(),
+      sampling: sampling,
+      exploration: 0.6,
+      tables: Tables::default(),
+    }
+  }
+
+  // Resume from tables saved with `checkpoint`.
+  pub fn resume(&mut self, path: &Path) -> Result<(), bincode::Error> {
+    self.tables = Tables::load(path)?;
+    Ok(())
+  }
+
+  pub fn checkpoint(&self, path: &Path) -> Result<(), bincode::Error> {
+    self.tables.save(path)
+  }
+
+  pub fn run<R: Rng>(&mut self, rng: &mut R, iterations: usize) -> Result<(), Error> {
+    for _ in 0..iterations {
+      self.iterate(rng)?;
+    }
+    Ok(())
+  }
+
+  pub fn iterate<R: Rng>(&mut self, rng: &mut R) -> Result<(), Error> {
+    self.tables.iterations += 1;
+
+    let players = self.sim.profile.players;
+    let player_first = self.tables.iterations % players;
+    let player_funds: Vec<(SeatId, Money)> =
+      self.player_funds.iter().cloned().enumerate().collect();
+
+    for seat_id in 0..players {
+      let state = GameState::deal(rng, self.sim, self.evaluator, &player_funds, player_first)?;
+
+      match self.sampling {
+        Sampling::External => {
+          self.external(rng, &state, seat_id)?;
+        }
+        Sampling::Outcome => {
+          self.outcome(rng, &state, seat_id, 1., 1., 1.)?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn strategy(&self) -> Strategy {
+    self.tables.strategy()
+  }
+
+  fn infoset_key(&self, state: &GameState, seat_id: SeatId) -> String {
+    use game::infoset_key_bucket;
+
+    match self.abstraction {
+      None => state.infoset_key(seat_id),
+      Some(abstraction) => {
+        let cards: Vec<Card> = state
+          .players_init
+          .iter()
+          .filter(|&&(s, _, _)| s == seat_id)
+          .flat_map(|&(_, _, ref cards)| cards.clone())
+          .collect();
+
+        infoset_key_bucket(
+          self.sim.profile.players,
+          state.player_first,
+          seat_id,
+          &cards,
+          &state.events,
+          abstraction,
+        )
+      }
+    }
+  }
+
+  // Regret matching over the legal actions.
+  fn sigma(&self, key: &String, legal: &[u8]) -> Vec<f32> {
+    let regrets: Vec<f32> = match self.tables.regrets.get(key) {
+      None => vec![0.; legal.len()],
+      Some(rs) => legal.iter().map(|&a| rs[a as usize].max(0.)).collect(),
+    };
+
+    let total: f32 = regrets.iter().sum();
+    if total > 0. {
+      regrets.iter().map(|r| r / total).collect()
+    } else {
+      vec![1. / legal.len() as f32; legal.len()]
+    }
+  }
+
+  fn external<R: Rng>(
+    &mut self,
+    rng: &mut R,
+    state: &GameState,
+    seat_id: SeatId,
+  ) -> Result<f32, Error> {
+    use anna_utils::math::sample;
+
+    if state.is_terminal() {
+      return Ok(payoff(state, seat_id));
+    }
+
+    let s = state.current_player().unwrap();
+    let legal = state.legal_actions();
+    let key = self.infoset_key(state, s);
+    let sigma = self.sigma(&key, &legal);
+    let size = self.sim.action_class.size();
+
+    if s == seat_id {
+      let mut values = Vec::with_capacity(legal.len());
+      for &action_i in legal.iter() {
+        values.push(self.external(rng, &state.apply(action_i)?, seat_id)?);
+      }
+
+      let value: f32 = values.iter().zip(sigma.iter()).map(|(v, p)| v * p).sum();
+
+      let regrets = self.tables.regrets.entry(key).or_insert(vec![0.; size]);
+      for (&action_i, v) in legal.iter().zip(values.iter()) {
+        regrets[action_i as usize] += v - value;
+      }
+
+      Ok(value)
+    } else {
+      {
+        let sums = self.tables.strategy_sum.entry(key).or_insert(vec![0.; size]);
+        for (&action_i, p) in legal.iter().zip(sigma.iter()) {
+          sums[action_i as usize] += p;
+        }
+      }
+
+      let i = sample(rng, sigma);
+      self.external(rng, &state.apply(legal[i])?, seat_id)
+    }
+  }
+
+  // Returns the sampled payoff of the updated seat and the probability of the trajectory tail,
+  // `pi_i` and `pi_o` being the reach probabilities of the seat and of the others, `q` the
+  // sampling probability.
+  fn outcome<R: Rng>(
+    &mut self,
+    rng: &mut R,
+    state: &GameState,
+    seat_id: SeatId,
+    pi_i: f32,
+    pi_o: f32,
+    q: f32,
+  ) -> Result<(f32, f32), Error> {
+    use anna_utils::math::sample;
+
+    if state.is_terminal() {
+      return Ok((payoff(state, seat_id) / q, 1.));
+    }
+
+    let s = state.current_player().unwrap();
+    let legal = state.legal_actions();
+    let key = self.infoset_key(state, s);
+    let sigma = self.sigma(&key, &legal);
+    let size = self.sim.action_class.size();
+
+    let probs: Vec<f32> = if s == seat_id {
+      let e = self.exploration;
+      sigma.iter().map(|p| e / legal.len() as f32 + (1. - e) * p).collect()
+    } else {
+      sigma.clone()
+    };
+
+    let i = sample(rng, probs.clone());
+    let (pi_i_, pi_o_) =
+      if s == seat_id { (pi_i * sigma[i], pi_o) } else { (pi_i, pi_o * sigma[i]) };
+
+    let (u, tail) =
+      self.outcome(rng, &state.apply(legal[i])?, seat_id, pi_i_, pi_o_, q * probs[i])?;
+
+    if s == seat_id {
+      let w = u * pi_o;
+
+      {
+        let regrets = self.tables.regrets.entry(key.clone()).or_insert(vec![0.; size]);
+        for (k, &action_i) in legal.iter().enumerate() {
+          if k == i {
+            regrets[action_i as usize] += w * tail * (1. - sigma[i]);
+          } else {
+            regrets[action_i as usize] -= w * tail * sigma[i];
+          }
+        }
+      }
+
+      let sums = self.tables.strategy_sum.entry(key).or_insert(vec![0.; size]);
+      for (&action_i, p) in legal.iter().zip(sigma.iter()) {
+        sums[action_i as usize] += pi_i / q * p;
+      }
+    }
+
+    Ok((u, tail * sigma[i]))
+  }
+}
+
+fn payoff(state: &GameState, seat_id: SeatId) -> f32 {
+  state
+    .payoffs()
+    .and_then(|payoffs| payoffs.iter().find(|&&(s, _)| s == seat_id).map(|&(_, m)| m as f32))
+    .unwrap_or(0.)
+}
+
+/** Abstraction bucketing hands by strength with equal width buckets.
+ **
+ ** The strength simulations are seeded by the cards, a hand always falls in the same bucket.
+ **/
+pub struct CardsStrengthBuckets<'a> {
+  pub eval: &'a Eval,
+  pub profile: Profile,
+  pub accuracy: usize,
+  pub buckets: usize,
+}
+
+impl<'a> CardsAbstraction for CardsStrengthBuckets<'a> {
+  fn bucket(&self, hand: &[Card], board: &[Card]) -> usize {
+    use anna_eval::strength::strength_;
+
+    let mut seed = [0; 32];
+    for (i, card) in hand.iter().chain(board.iter()).take(32).enumerate() {
+      seed[i] = card.as_u8() + 1;
+    }
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    let score = strength_(
+      &mut rng,
+      self.eval,
+      &self.profile.rounds,
+      self.profile.players,
+      &self.profile.deck,
+      self.accuracy,
+      &hand.to_vec(),
+      &board.to_vec(),
+    );
+
+    ((score * self.buckets as f32) as usize).min(self.buckets - 1)
+  }
+}
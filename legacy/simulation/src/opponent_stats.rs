@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anna_model::{cards::Card, Action, ActionClass, Money};
+use engine::Score;
+use players::Players;
+use Event;
+use SeatId;
+
+/** HUD statistics of a seat, as counts over the observed hands.
+ **
+ ** Flops and showdowns are only counted for games whose end is known (`OpponentStats::game_end`).
+ **/
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeatStats {
+  pub hands: usize,
+  pub vpip: usize, // Hands with a voluntary call or raise preflop.
+  pub pfr: usize,  // Hands raised preflop.
+  pub three_bet: usize,
+  pub three_bet_chances: usize, // Hands facing a single raise preflop.
+  pub bets: usize,              // Bets and raises after the flop.
+  pub calls: usize,             // Calls after the flop, checks excluded.
+  pub flops: usize,
+  pub showdowns: usize,
+}
+
+fn ratio(n: usize, d: usize) -> Option<f32> {
+  if d == 0 {
+    None
+  } else {
+    Some(n as f32 / d as f32)
+  }
+}
+
+impl SeatStats {
+  // Voluntarily put money in pot.
+  pub fn vpip(&self) -> Option<f32> {
+    ratio(self.vpip, self.hands)
+  }
+
+  // Preflop raise.
+  pub fn pfr(&self) -> Option<f32> {
+    ratio(self.pfr, self.hands)
+  }
+
+  pub fn three_bet(&self) -> Option<f32> {
+    ratio(self.three_bet, self.three_bet_chances)
+  }
+
+  // Aggression factor.
+  pub fn af(&self) -> Option<f32> {
+    ratio(self.bets, self.calls)
+  }
+
+  // Went to showdown, once the flop is seen.
+  pub fn wtsd(&self) -> Option<f32> {
+    ratio(self.showdowns, self.flops)
+  }
+}
+
+// Flags of a seat in the current hand.
+#[derive(Clone, Debug, Default)]
+struct HandFlags {
+  vpip: bool,
+  pfr: bool,
+  three_bet: bool,
+  three_bet_chance: bool,
+  flop: bool,
+  folded: bool,
+}
+
+/** Opponent statistics
+ **
+ ** Built from the event stream of every game, as seen by `Players::play` once concatenated, the
+ ** blinds being given by `Blind` events ahead of the actions instead of the leading raises.
+ **/
+#[derive(Clone)]
+pub struct OpponentStats<'a> {
+  action_class: &'a ActionClass,
+  pub seats: HashMap<SeatId, SeatStats>,
+
+  hand: HashMap<SeatId, HandFlags>,
+  blind_biggest: Money,
+  blind_big: Option<SeatId>, // None with antes.
+  round_id: usize,
+  round_bet: bool, // Some bet to call in the round.
+  preflop_raises: usize,
+}
+
+impl<'a> OpponentStats<'a> {
+  pub fn new(action_class: &'a ActionClass) -> OpponentStats<'a> {
+    OpponentStats {
+      action_class: action_class,
+      seats: HashMap::new(),
+      hand: HashMap::new(),
+      blind_biggest: Money::zero(),
+      blind_big: None,
+      round_id: 0,
+      round_bet: true,
+      preflop_raises: 0,
+    }
+  }
+
+  pub fn seat(&self, seat_id: SeatId) -> SeatStats {
+    self.seats.get(&seat_id).cloned().unwrap_or(SeatStats::default())
+  }
+
+  // Start a game, the hand of the previous one being counted if not already.
+  pub fn game_start(&mut self, player_funds: &[Money]) -> () {
+    self.hand_end(None);
+
+    self.hand = player_funds
+      .iter()
+      .enumerate()
+      .filter(|&(_, &m)| m > Money::zero())
+      .map(|(seat_id, _)| (seat_id, HandFlags::default()))
+      .collect();
+    self.blind_biggest = Money::zero();
+    self.blind_big = None;
+    self.round_id = 0;
+    self.round_bet = true;
+    self.preflop_raises = 0;
+  }
+
+  pub fn game_end(&mut self, score: &Score) -> () {
+    self.hand_end(Some(score));
+  }
+
+  pub fn observe(&mut self, events: &[Event]) -> () {
+    for event in events {
+      match event {
+        &Event::Table { .. } => {
+          self.round_id += 1;
+          self.round_bet = false;
+
+          if self.round_id == 1 {
+            for flags in self.hand.values_mut().filter(|flags| !flags.folded) {
+              flags.flop = true;
+            }
+          }
+        }
+        &Event::Blind { seat_id, amount } => {
+          if amount > self.blind_biggest {
+            self.blind_biggest = amount;
+            self.blind_big = Some(seat_id);
+          } else if amount == self.blind_biggest {
+            self.blind_big = None;
+          }
+        }
+        &Event::Play(ref act) => {
+          let action = self.action_class.to_action(act.action);
+          let preflop = self.round_id == 0;
+          let preflop_raises = self.preflop_raises;
+          let blind_big = self.blind_big;
+          let round_bet = self.round_bet;
+
+          let flags = self.hand.entry(act.seat_id).or_insert(HandFlags::default());
+
+          if preflop && preflop_raises == 1 {
+            flags.three_bet_chance = true;
+          }
+
+          let seat = self.seats.entry(act.seat_id).or_insert(SeatStats::default());
+
+          match action {
+            Action::Fold => {
+              flags.folded = true;
+            }
+            // The big blind checking its option is not voluntary.
+            Action::Call if preflop => {
+              if preflop_raises > 0 || blind_big != Some(act.seat_id) {
+                flags.vpip = true;
+              }
+            }
+            Action::Call => {
+              if round_bet {
+                seat.calls += 1;
+              }
+            }
+            Action::Raise if preflop => {
+              flags.vpip = true;
+              flags.pfr = true;
+              if preflop_raises == 1 {
+                flags.three_bet = true;
+              }
+            }
+            Action::Raise => {
+              seat.bets += 1;
+            }
+          }
+
+          if action == Action::Raise {
+            self.round_bet = true;
+            if preflop {
+              self.preflop_raises += 1;
+            }
+          }
+        }
+        // Rest of the summary of a logged game, the end being known from `game_end`.
+        _ => {}
+      }
+    }
+  }
+
+  // Count the flags of the hand, the showdown being known from the score.
+  fn hand_end(&mut self, score: Option<&Score>) -> () {
+    let showdown: Option<Vec<SeatId>> = score.map(|score| match score.pots.first() {
+      Some(pot) if pot.seats.len() > 1 => pot.seats.clone(),
+      _ => Vec::new(),
+    });
+
+    for (seat_id, flags) in self.hand.drain() {
+      let seat = self.seats.entry(seat_id).or_insert(SeatStats::default());
+
+      seat.hands += 1;
+      seat.vpip += flags.vpip as usize;
+      seat.pfr += flags.pfr as usize;
+      seat.three_bet += flags.three_bet as usize;
+      seat.three_bet_chances += flags.three_bet_chance as usize;
+
+      if let Some(ref showdown) = showdown {
+        if flags.flop {
+          seat.flops += 1;
+          seat.showdowns += showdown.contains(&seat_id) as usize;
+        }
+      }
+    }
+  }
+}
+
+/** Players keeping `OpponentStats` of every seat across games.
+ **
+ ** The events given to each seat are merged back into the game history, the blinds being
+ ** observed as `Blind` events when the game starts. Actions following the last play of the
+ ** wrapped seats are not seen, showdowns are known through `game_end`.
+ **/
+pub struct PlayersStats<'a, P> {
+  pub players: P,
+  pub stats: OpponentStats<'a>,
+  events: usize,                // Events of the game observed so far.
+  acts: HashMap<SeatId, usize>, // Index of the last act of a seat.
+  blinds_plays: usize,          // Leading raises of the blinds in the history.
+}
+
+// Blinds posted by the seats with funds from the first player, as the engine does.
+fn blinds_posted(
+  blinds: &[Money],
+  player_first: SeatId,
+  player_funds: &[Money],
+) -> Vec<(SeatId, Money)> {
+  use rounds::seat_next_active;
+
+  let actives: Vec<SeatId> =
+    (0..player_funds.len()).filter(|&i| player_funds[i] > Money::zero()).collect();
+
+  let mut seat_id = player_first;
+  let mut posts = Vec::new();
+  for &blind in blinds.iter().take(actives.len()) {
+    posts.push((seat_id, blind));
+    seat_id = seat_next_active(player_funds.len(), &actives, seat_id).unwrap_or(seat_id);
+  }
+  posts
+}
+
+impl<'a, P> PlayersStats<'a, P> {
+  pub fn new(players: P, action_class: &'a ActionClass) -> PlayersStats<'a, P> {
+    PlayersStats {
+      players: players,
+      stats: OpponentStats::new(action_class),
+      events: 0,
+      acts: HashMap::new(),
+      blinds_plays: 0,
+    }
+  }
+}
+
+impl<'a, P: Players<E>, E> Players<E> for PlayersStats<'a, P> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    let blind_biggest = *blinds.iter().max().unwrap();
+    let posts = blinds_posted(blinds, player_first, player_funds);
+
+    self.stats.game_start(player_funds);
+    self.stats.observe(
+      &posts
+        .iter()
+        .map(|&(seat_id, amount)| Event::Blind { seat_id: seat_id, amount: amount })
+        .collect::<Vec<_>>(),
+    );
+    self.events = 0;
+    self.acts = HashMap::new();
+    self.blinds_plays = if blinds.iter().all(|&m| m == blind_biggest) { 0 } else { posts.len() };
+
+    self.players.init(blinds, player_first, player_funds, playing_hands)
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, E> {
+    // Events start from the game start on the first play of a seat, from its last act after.
+    let start = self.acts.get(&seat_id).cloned().unwrap_or(0);
+    let seen = (self.events - start).min(events.len());
+    let blinds = self.blinds_plays.saturating_sub(self.events).min(events.len() - seen);
+    self.stats.observe(&events[seen + blinds..]);
+    self.events += events.len() - seen;
+    self.acts.insert(seat_id, self.events);
+
+    self.players.play(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  // The events are only observed by `play`, asked after the policy.
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.players.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.stats.game_end(score);
+    self.players.game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.players.deadline(seat_id, deadline)
+  }
+}
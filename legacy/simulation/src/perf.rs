@@ -1,8 +1,12 @@
+use aivat::{Aivat, ValueEstimate};
 use anna_eval::Eval;
 use anna_model::{cards::Card, Money};
+use engine::{Log, Score};
+use game::GameState;
 use players::Players;
 use rand::Rng;
-use std::fmt::Debug;
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
 use SeatId;
 use Sim;
 
@@ -80,3 +84,497 @@ pub fn run_benchmark<E: Debug, P: Players<E>>(
 
   players_rates.iter().map(math::mean).collect()
 }
+
+// Rate of `seat_id` broken down by position, street, showdown and starting hand, see `Breakdown`.
+pub fn run_benchmark_breakdown<E: Debug, P: Players<E>>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+  seat_id: SeatId,
+) -> Breakdown {
+  use engine::table_game_simulate;
+  use rand::thread_rng;
+
+  let ref mut rng = thread_rng();
+
+  let seats: Vec<SeatId> = player_funds.iter().map(|&(seat_id, _)| seat_id).collect();
+  let mut breakdown = Breakdown::new();
+
+  for n in 0..hands {
+    let player_first = seats[n % seats.len()];
+
+    let (log, score) =
+      table_game_simulate(rng, sim, evaluator, players, player_funds, player_first)
+        .map_err(|(_, err)| err)
+        .expect("simulation failed.");
+
+    breakdown.add(sim, &log, &score, player_first, seat_id);
+  }
+
+  breakdown
+}
+
+/** Duplicate benchmark
+ **
+ ** Every deal, board included, is replayed once by seat with the seats rotated, so that each
+ ** player plays every position with the same cards. Returns the mean rate of every seat.
+ **/
+pub fn run_benchmark_duplicate<E: Debug, P: Players<E>>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+) -> Vec<Rate> {
+  use rand::thread_rng;
+
+  let ref mut rng = thread_rng();
+
+  let deals = (hands / player_funds.len()).max(1);
+  let deals_rates = duplicate_rates(rng, sim, evaluator, player_funds, deals, players);
+
+  (0..sim.profile.players)
+    .map(|seat_id| deals_rates.iter().map(|rates| rates[seat_id]).sum::<f32>() / deals as f32)
+    .collect()
+}
+
+// Statistics of every seat rate over duplicate deals, see `run_benchmark_duplicate`.
+pub fn run_benchmark_stats<E: Debug, P: Players<E>>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+) -> Vec<RateStats> {
+  use rand::thread_rng;
+
+  let ref mut rng = thread_rng();
+
+  let deals = (hands / player_funds.len()).max(1);
+  let deals_rates = duplicate_rates(rng, sim, evaluator, player_funds, deals, players);
+
+  (0..sim.profile.players)
+    .map(|seat_id| RateStats::new(&deals_rates.iter().map(|rates| rates[seat_id]).collect()))
+    .collect()
+}
+
+/** Sequential benchmark
+ **
+ ** Plays duplicate deals by batches until `sprt` decides on the rate of `seat_id`, or until
+ ** `hands` hands are played.
+ **/
+pub fn run_benchmark_sprt<E: Debug, P: Players<E>>(
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+  seat_id: SeatId,
+  sprt: &Sprt,
+) -> (RateStats, Decision) {
+  use rand::thread_rng;
+
+  let ref mut rng = thread_rng();
+
+  let batch = 16;
+  let deals_max = (hands / player_funds.len()).max(1);
+
+  let mut rates = Vec::new();
+
+  loop {
+    let deals = batch.min(deals_max - rates.len());
+    for deal_rates in duplicate_rates(rng, sim, evaluator, player_funds, deals, players) {
+      rates.push(deal_rates[seat_id]);
+    }
+
+    let stats = RateStats::new(&rates);
+    let decision = sprt.decide(&stats);
+
+    if decision != Decision::Undecided || rates.len() >= deals_max {
+      return (stats, decision);
+    }
+  }
+}
+
+// Rates by seat of every deal, averaged over the rotations of the deal.
+pub fn duplicate_rates<E: Debug, P: Players<E>, R: Rng>(
+  rng: &mut R,
+  sim: &Sim,
+  evaluator: &Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  deals: usize,
+  players: &mut P,
+) -> Vec<Vec<Rate>> {
+  use engine::{table_deal, table_game_play};
+
+  let seats = player_funds.len();
+  let mut deals_rates = Vec::with_capacity(deals);
+
+  for deal_id in 0..deals {
+    // Private cards by position, starting with the first player.
+    let (players_init, table_cards) = table_deal(rng, sim, player_funds);
+    let cards: Vec<Vec<Card>> = players_init.iter().map(|&(_, _, ref cs)| cs.clone()).collect();
+
+    let mut rates = vec![0.; sim.profile.players];
+
+    for rotation in 0..seats {
+      let first = (deal_id + rotation) % seats;
+      let players_init: Vec<(SeatId, Money, Vec<Card>)> = player_funds
+        .iter()
+        .enumerate()
+        .map(|(i, &(seat_id, m))| (seat_id, m, cards[(i + seats - first) % seats].clone()))
+        .collect();
+
+      let (log, score) = table_game_play(
+        sim,
+        evaluator,
+        players,
+        &players_init,
+        &table_cards,
+        player_funds[first].0,
+      )
+      .map_err(|(_, err)| err)
+      .expect("simulation failed.");
+
+      for (seat_id, rate) in rate_players(sim.blind_biggest, &log.players_init, &score.player_funds)
+      {
+        rates[seat_id] += rate / seats as f32;
+      }
+    }
+
+    deals_rates.push(rates);
+  }
+
+  deals_rates
+}
+
+/** Benchmark with AIVAT
+ **
+ ** Mean rates of every seat as `run_benchmark`, along with the rate of `seat_id` corrected by
+ ** `aivat`, `policy` being the strategy of the seat player.
+ **/
+pub fn run_benchmark_aivat<'a, E, P, V, F>(
+  sim: &'a Sim<'a>,
+  evaluator: &'a Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+  aivat: &Aivat<'a, V>,
+  seat_id: SeatId,
+  policy: F,
+) -> (Vec<Rate>, Rate)
+where
+  E: Debug,
+  P: Players<E>,
+  V: ValueEstimate,
+  F: FnMut(&GameState) -> Vec<f32>,
+{
+  use anna_utils::math;
+  use rand::thread_rng;
+
+  let ref mut rng = thread_rng();
+
+  let hands_rates =
+    aivat_rates(rng, sim, evaluator, player_funds, hands, players, aivat, seat_id, policy);
+
+  let rates = (0..sim.profile.players)
+    .map(|s| math::mean(&hands_rates.iter().map(|&(ref rates, _)| rates[s]).collect()))
+    .collect();
+  let rate_aivat = math::mean(&hands_rates.iter().map(|&(_, r)| r).collect());
+
+  (rates, rate_aivat)
+}
+
+// Rates by seat of every hand, along with the AIVAT rate of `seat_id`.
+pub fn aivat_rates<'a, E, P, V, F, R>(
+  rng: &mut R,
+  sim: &'a Sim<'a>,
+  evaluator: &'a Eval,
+  player_funds: &Vec<(SeatId, Money)>,
+  hands: usize,
+  players: &mut P,
+  aivat: &Aivat<'a, V>,
+  seat_id: SeatId,
+  mut policy: F,
+) -> Vec<(Vec<Rate>, Rate)>
+where
+  E: Debug,
+  P: Players<E>,
+  V: ValueEstimate,
+  F: FnMut(&GameState) -> Vec<f32>,
+  R: Rng,
+{
+  use engine::table_game_simulate;
+
+  let blind = sim.blind_biggest.unpack() as f32;
+  let mut hands_rates = Vec::with_capacity(hands);
+
+  for n in 0..hands {
+    let player_first = player_funds[n % player_funds.len()].0;
+
+    let (log, score) =
+      table_game_simulate(rng, sim, evaluator, players, player_funds, player_first)
+        .map_err(|(_, err)| err)
+        .expect("simulation failed.");
+
+    let mut rates = vec![0.; sim.profile.players];
+    for (s, rate) in rate_players(sim.blind_biggest, &log.players_init, &score.player_funds) {
+      rates[s] = rate;
+    }
+
+    let chips = aivat
+      .correct(rng, sim, evaluator, &log, player_first, seat_id, &mut policy)
+      .expect("AIVAT replay failed.");
+
+    hands_rates.push((rates, 1000. * chips / blind));
+  }
+
+  hands_rates
+}
+
+/** Rate statistics
+ **
+ ** Mean and standard deviation of independent rate samples, hands or duplicate deals.
+ **/
+#[derive(Clone, Copy, Debug)]
+pub struct RateStats {
+  pub mean: Rate,
+  pub std: f32,
+  pub samples: usize,
+}
+
+impl RateStats {
+  pub fn new(rates: &Vec<Rate>) -> RateStats {
+    use anna_utils::math;
+
+    RateStats {
+      mean: if rates.is_empty() { 0. } else { math::mean(rates) },
+      std: if rates.len() < 2 { 0. } else { math::var(rates).sqrt() },
+      samples: rates.len(),
+    }
+  }
+
+  pub fn std_error(&self) -> f32 {
+    if self.samples == 0 {
+      0.
+    } else {
+      self.std / (self.samples as f32).sqrt()
+    }
+  }
+
+  // 95% confidence interval of the mean.
+  pub fn interval(&self) -> (Rate, Rate) {
+    let e = 1.96 * self.std_error();
+    (self.mean - e, self.mean + e)
+  }
+
+  // Mean significantly above the one of `other` (Welch, 95% one-sided).
+  pub fn is_better(&self, other: &RateStats) -> bool {
+    let e = (self.std_error().powi(2) + other.std_error().powi(2)).sqrt();
+    self.mean - other.mean > 1.645 * e
+  }
+}
+
+impl fmt::Display for RateStats {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (low, high) = self.interval();
+    write!(
+      f,
+      "{} [{}, {}] (std {}, {} samples)",
+      rate_format(self.mean),
+      rate_format(low),
+      rate_format(high),
+      rate_format(self.std),
+      self.samples
+    )
+  }
+}
+
+/** Rate breakdown
+ **
+ ** Rates of a seat by hand, grouped by
+ ** - position, as seats after the button (the button being 0)
+ ** - street, the round the game ended on
+ ** - showdown or not, the showdown (blue) and non-showdown (red) lines
+ ** - starting hand class, see `hand_class`
+ **
+ ** The share of a group is its contribution to the overall rate, the shares of the groups of a
+ ** breakdown summing to the overall rate.
+ **/
+#[derive(Clone, Debug, Default)]
+pub struct Breakdown {
+  pub rates: Vec<Rate>,
+  pub positions: BTreeMap<usize, Vec<Rate>>,
+  pub streets: BTreeMap<usize, Vec<Rate>>,
+  pub showdown: Vec<Rate>,
+  pub showdown_non: Vec<Rate>,
+  pub hand_classes: BTreeMap<String, Vec<Rate>>,
+}
+
+impl Breakdown {
+  pub fn new() -> Breakdown {
+    Breakdown::default()
+  }
+
+  // Add the game of a log to the breakdown of `seat_id`, skipped when not dealt in.
+  pub fn add(
+    &mut self,
+    sim: &Sim,
+    log: &Log,
+    score: &Score,
+    player_first: SeatId,
+    seat_id: SeatId,
+  ) -> () {
+    use engine::showdown_hands;
+    use rounds::seat_previous;
+
+    let players = sim.profile.players;
+
+    let cards = match log.players_init.iter().find(|&&(s, _, _)| s == seat_id) {
+      Some(&(_, _, ref cards)) => cards,
+      None => return,
+    };
+    let rate = match rate_players(sim.blind_biggest, &log.players_init, &score.player_funds)
+      .into_iter()
+      .find(|&(s, _)| s == seat_id)
+    {
+      Some((_, rate)) => rate,
+      None => return,
+    };
+
+    let dealer = seat_previous(players, player_first);
+    let position = (seat_id + players - dealer) % players;
+    let street = log.rounds.len().saturating_sub(1);
+    let shown = showdown_hands(&log.players_init, score).iter().any(|&(s, _)| s == seat_id);
+
+    self.rates.push(rate);
+    self.positions.entry(position).or_insert_with(Vec::new).push(rate);
+    self.streets.entry(street).or_insert_with(Vec::new).push(rate);
+    if shown {
+      self.showdown.push(rate);
+    } else {
+      self.showdown_non.push(rate);
+    }
+    self.hand_classes.entry(hand_class(cards)).or_insert_with(Vec::new).push(rate);
+  }
+
+  // Contribution of the rates of a group to the overall rate.
+  pub fn share(&self, rates: &[Rate]) -> Rate {
+    if self.rates.is_empty() {
+      0.
+    } else {
+      rates.iter().sum::<Rate>() / self.rates.len() as f32
+    }
+  }
+}
+
+impl fmt::Display for Breakdown {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let line = |f: &mut fmt::Formatter, name: String, rates: &[Rate]| -> fmt::Result {
+      let stats = RateStats::new(&rates.to_vec());
+      writeln!(f, "  {:10} {:>10} | {}", name, rate_format(self.share(rates)), stats)
+    };
+
+    writeln!(f, "overall: {}", RateStats::new(&self.rates))?;
+
+    writeln!(f, "position (button 0):")?;
+    for (position, rates) in self.positions.iter() {
+      line(f, format!("{}", position), rates)?;
+    }
+
+    writeln!(f, "street:")?;
+    for (street, rates) in self.streets.iter() {
+      line(f, format!("{}", street), rates)?;
+    }
+
+    writeln!(f, "showdown:")?;
+    line(f, "blue".to_string(), &self.showdown)?;
+    line(f, "red".to_string(), &self.showdown_non)?;
+
+    writeln!(f, "starting hand:")?;
+    for (class, rates) in self.hand_classes.iter() {
+      line(f, class.clone(), rates)?;
+    }
+
+    Ok(())
+  }
+}
+
+/** Starting hand class
+ **
+ ** Card values from the highest, suffixed for two cards by `s` when suited and `o` otherwise,
+ ** e.g. `AKs`, `QJo`, `77` or `K` for Kuhn and Leduc.
+ **/
+pub fn hand_class(cards: &[Card]) -> String {
+  let mut values: Vec<_> = cards.iter().map(|c| c.value).collect();
+  values.sort_by(|a, b| b.cmp(a));
+
+  let mut class: String = values.iter().map(|v| v.to_string()).collect();
+  if cards.len() == 2 && cards[0].value != cards[1].value {
+    class.push(if cards[0].suit == cards[1].suit { 's' } else { 'o' });
+  }
+
+  class
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Decision {
+  Better,
+  Worse,
+  Undecided,
+}
+
+/** Sequential probability ratio test
+ **
+ ** Tests a mean rate of `delta` (better) against `-delta` (worse) with error rates `alpha` and
+ ** `beta`, rates being taken as normal with the sample variance.
+ **
+ ** ref: https://en.wikipedia.org/wiki/Sequential_probability_ratio_test
+ **/
+#[derive(Clone, Debug)]
+pub struct Sprt {
+  pub delta: Rate,
+  pub alpha: f32,
+  pub beta: f32,
+  pub samples_min: usize, // Before trusting the sample variance.
+}
+
+impl Sprt {
+  pub fn new(delta: Rate) -> Sprt {
+    Sprt { delta: delta, alpha: 0.05, beta: 0.05, samples_min: 32 }
+  }
+
+  // Log-likelihood ratio of the better hypothesis over the worse one.
+  pub fn llr(&self, stats: &RateStats) -> f32 {
+    let var = stats.std * stats.std;
+    let sum = stats.mean * stats.samples as f32;
+
+    if var > 0. {
+      2. * self.delta * sum / var
+    } else if sum > 0. {
+      ::std::f32::INFINITY
+    } else if sum < 0. {
+      ::std::f32::NEG_INFINITY
+    } else {
+      0.
+    }
+  }
+
+  pub fn decide(&self, stats: &RateStats) -> Decision {
+    if stats.samples < self.samples_min {
+      return Decision::Undecided;
+    }
+
+    let llr = self.llr(stats);
+
+    if llr >= ((1. - self.beta) / self.alpha).ln() {
+      Decision::Better
+    } else if llr <= (self.beta / (1. - self.alpha)).ln() {
+      Decision::Worse
+    } else {
+      Decision::Undecided
+    }
+  }
+}
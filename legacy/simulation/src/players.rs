@@ -1,10 +1,12 @@
 use std::collections::HashSet;
+use std::time::Instant;
 
 use rand;
 
 use anna_eval::Eval;
 use anna_model::{cards::Card, ActionClass, Money};
 
+use engine::{Score, TableView};
 use Event;
 use SeatId;
 
@@ -28,6 +30,99 @@ pub trait Players<E> {
     player_fund: Money,
     events: &[Event],
   ) -> Result<u8, E>;
+
+  /** Probabilities of the actions `play` would pick from, illegal actions having none. Empty
+   ** when the player does not expose its policy.
+   **
+   ** Takes the same arguments as `play` and leaves the player state untouched, so that it can be
+   ** asked before `play` at the same decision.
+   **/
+  fn policy(
+    &mut self,
+    _: usize,
+    _: Money,
+    _: Option<Money>,
+    _: &[Money],
+    _: usize,
+    _: Money,
+    _: &[Event],
+  ) -> Vec<f32> {
+    Vec::new()
+  }
+
+  /** Notify the end of a game, with its final score and the hands shown at showdown (none when
+   ** the game was won uncontested).
+   **/
+  fn game_end(&mut self, _: &Score, _: &[(SeatId, Vec<Card>)]) -> () {}
+
+  /** Time by which the next `play` of the seat has to return, given before every play (none
+   ** without `Budget`). An action returned later is discarded by the engine, players waiting on
+   ** others (remote ones) give up at the deadline.
+   **/
+  fn deadline(&mut self, _: SeatId, _: Option<Instant>) -> () {}
+}
+
+// Players of different types behind a box, as when picked at runtime.
+impl<'a, E> Players<E> for Box<Players<E> + 'a> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    (**self).init(blinds, player_first, player_funds, playing_hands)
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, E> {
+    (**self).play(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    (**self).policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    (**self).game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    (**self).deadline(seat_id, deadline)
+  }
 }
 
 #[derive(Clone)]
@@ -104,6 +199,30 @@ impl<'a, R: rand::Rng> Players<()> for PlayersRand<'a, R> {
   ) -> Result<u8, ()> {
     use anna_utils::math;
 
+    let probs = self.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      &[],
+    );
+    let action = math::sample(&mut self.rng, probs);
+    Ok(action as u8)
+  }
+
+  // Uniform over the legal actions.
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    _: &[Event],
+  ) -> Vec<f32> {
     let mask = self.action_class.normalize(
       self.blind_biggest,
       round_id,
@@ -113,10 +232,8 @@ impl<'a, R: rand::Rng> Players<()> for PlayersRand<'a, R> {
       player_pots[seat_id],
     );
 
-    let probs =
-      (0..self.action_class.size()).map(|i| if mask.contains(&i) { 1.0 } else { 0.0 }).collect();
-    let action = math::sample(&mut self.rng, probs);
-    Ok(action as u8)
+    let n = mask.len() as f32;
+    (0..self.action_class.size()).map(|i| if mask.contains(&i) { 1. / n } else { 0. }).collect()
   }
 }
 
@@ -151,6 +268,10 @@ pub trait Table<E> {
     round_id: usize,
     table_target: Money,
   ) -> Result<Vec<Card>, E>;
+
+  // Auditing hooks, given the table before a seat acts and once its action is applied.
+  fn audit_decision(&mut self, _: &TableView) -> () {}
+  fn audit_action(&mut self, _: &TableView, _action: u8) -> () {}
 }
 
 pub struct TableStatic<'a, E: 'a> {
@@ -165,18 +286,8 @@ impl<'a, E> Table<E> for TableStatic<'a, E> {
   }
 
   fn round_start(&mut self, _: &Eval, round_id: usize, _: Money) -> Result<Vec<Card>, E> {
-    let mut skips = 0;
-    let mut takes = 0;
-
-    for i in 1..(round_id + 1) {
-      if round_id == i {
-        takes = self.rounds[i];
-      } else {
-        skips += self.rounds[i];
-      }
-    }
-
-    Ok(self.table_cards.iter().skip(skips).take(takes).map(|&c| c).collect())
+    use rounds::round_cards;
+    Ok(round_cards(&self.rounds, &self.table_cards, round_id))
   }
 }
 
@@ -211,4 +322,33 @@ impl<'a, E> Players<E> for TableStatic<'a, E> {
       events,
     )
   }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.players.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.players.game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.players.deadline(seat_id, deadline)
+  }
 }
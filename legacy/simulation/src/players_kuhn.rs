@@ -81,12 +81,35 @@ impl<'a, R: Rng> Players<()> for Kuhn2<'a, R> {
     player_pots: &[Money],
     seat_id: usize,
     player_fund: Money,
-    _: &[Event],
+    events: &[Event],
   ) -> Result<u8, ()> {
     use anna_utils::math::sample;
 
-    //println!("{}", events.len());
+    let probs = self.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    );
+
+    self.players_acts[seat_id] += 1;
 
+    Ok(sample(&mut self.rng, probs) as u8)
+  }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    _: &[Event],
+  ) -> Vec<f32> {
     let card =
       KUHN_CARDS.binary_search(&self.players_card.get(&seat_id).expect("Card not found.")).unwrap();
 
@@ -176,8 +199,7 @@ impl<'a, R: Rng> Players<()> for Kuhn2<'a, R> {
       }
     }
 
-    self.players_acts[seat_id] += 1;
-
-    Ok(sample(&mut self.rng, probs) as u8)
+    let total: f32 = probs.iter().sum();
+    probs.iter().map(|p| p / total).collect()
   }
 }
@@ -18,6 +18,7 @@ pub struct Lex<'a, R: 'a> {
   // player_first: SeatId,
   players_cards: HashMap<SeatId, Vec<Card>>,
   table_cards: Vec<Card>,
+  strength: Option<(SeatId, Vec<Card>, f32)>, // Estimated by `policy`, for the next `play`.
 }
 
 impl<'a, R: Rng> Lex<'a, R> {
@@ -38,10 +39,83 @@ impl<'a, R: Rng> Lex<'a, R> {
       // player_first: 0,
       players_cards: HashMap::new(),
       table_cards: Vec::new(),
+      strength: None,
+    }
+  }
+
+  // Strength of the hand of a seat, estimated once per decision with a rng seeded from the player.
+  fn strength_estimate(&mut self, seat_id: SeatId, table_cards: &Vec<Card>) -> f32 {
+    use anna_eval::strength::strength;
+    use anna_utils::random::rseed;
+
+    match self.strength.take() {
+      Some((s, ref cards, x)) if s == seat_id && cards == table_cards => x,
+      _ => {
+        let ref mut rng = rseed(&mut self.rng);
+        let hand = self.players_cards.get(&seat_id).unwrap();
+        strength(rng, self.eval, self.profile, self.accuracy, hand, table_cards)
+      }
+    }
+  }
+
+  // Table cards once the events are observed.
+  fn table_cards_after(&self, events: &[Event]) -> Vec<Card> {
+    let mut table_cards = self.table_cards.clone();
+    for event in events {
+      match event {
+        &Event::Table { ref cards } => {
+          table_cards.extend(cards.iter());
+        }
+        _ => {}
+      }
+    }
+    table_cards
+  }
+
+  // Probabilities of fold, call and raise for the strength of the hand, before `action_legal`.
+  fn probs(
+    &self,
+    strength: f32,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+  ) -> Vec<f32> {
+    use anna_eval::strength::return_rate;
+
+    let target = Money::from_i32(table_target - player_pots[seat_id]).unwrap();
+    let raise = table_target_raise.unwrap_or(Money::zero());
+    let return_rate = return_rate(strength, player_pots, target + raise);
+
+    if return_rate < 0.8 {
+      vec![0.95, 0.00, 0.05]
+    } else if return_rate < 1.0 {
+      vec![0.80, 0.05, 0.15]
+    } else if return_rate < 1.3 {
+      vec![0.00, 0.60, 0.40]
+    } else {
+      vec![0.00, 0.30, 0.70]
     }
   }
 }
 
+// If fold and amount to call is zero, then call, as when raises are capped.
+fn action_legal(
+  table_target: Money,
+  table_target_raise: Option<Money>,
+  player_pots: &[Money],
+  seat_id: usize,
+  action_i: usize,
+) -> u8 {
+  if action_i == 0 && table_target == player_pots[seat_id] {
+    1
+  } else if action_i == 2 && table_target_raise.is_none() {
+    1
+  } else {
+    action_i as u8
+  }
+}
+
 impl<'a, R: Rng> Players<()> for Lex<'a, R> {
   fn init(
     &mut self,
@@ -58,6 +132,7 @@ impl<'a, R: Rng> Players<()> for Lex<'a, R> {
     }
 
     self.table_cards = Vec::new();
+    self.strength = None;
   }
 
   fn play(
@@ -70,44 +145,40 @@ impl<'a, R: Rng> Players<()> for Lex<'a, R> {
     _: Money,
     events: &[Event],
   ) -> Result<u8, ()> {
-    use anna_eval::strength::{return_rate, strength};
     use anna_utils::math;
 
-    for event in events {
-      match event {
-        &Event::Table { ref cards } => {
-          self.table_cards.extend(cards.iter());
-        }
-        _ => {}
-      }
-    }
-
-    let hand = self.players_cards.get(&seat_id).unwrap();
-    let strength =
-      strength(&mut self.rng, self.eval, self.profile, self.accuracy, hand, &self.table_cards);
-    let target = Money::from_i32(table_target - player_pots[seat_id]).unwrap();
-    let raise = table_target_raise.unwrap_or(Money::zero());
-    let return_rate = return_rate(strength, player_pots, target + raise);
+    self.table_cards = self.table_cards_after(events);
 
-    let probs = if return_rate < 0.8 {
-      vec![0.95, 0.00, 0.05]
-    } else if return_rate < 1.0 {
-      vec![0.80, 0.05, 0.15]
-    } else if return_rate < 1.3 {
-      vec![0.00, 0.60, 0.40]
-    } else {
-      vec![0.00, 0.30, 0.70]
-    };
+    // Estimated at every decision.
+    let table_cards = self.table_cards.clone();
+    let strength = self.strength_estimate(seat_id, &table_cards);
 
+    let probs = self.probs(strength, table_target, table_target_raise, player_pots, seat_id);
     let action_i = math::sample(&mut self.rng, probs);
+    Ok(action_legal(table_target, table_target_raise, player_pots, seat_id, action_i))
+  }
 
-    // If fold and amount to call is zero, then call.
-    if action_i == 0 && target == Money::zero() {
-      Ok(1)
-    } else if action_i == 2 && table_target_raise.is_none() {
-      Ok(1)
-    } else {
-      Ok(action_i as u8)
+  fn policy(
+    &mut self,
+    _: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    _: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    // Same strength as the next `play`, which doesn't estimate it again.
+    let table_cards = self.table_cards_after(events);
+    let strength = self.strength_estimate(seat_id, &table_cards);
+    self.strength = Some((seat_id, table_cards, strength));
+
+    let mut probs = vec![0.; 3];
+    let probs_raw = self.probs(strength, table_target, table_target_raise, player_pots, seat_id);
+    for (action_i, p) in probs_raw.into_iter().enumerate() {
+      let action_i = action_legal(table_target, table_target_raise, player_pots, seat_id, action_i);
+      probs[action_i as usize] += p;
     }
+    probs
   }
 }
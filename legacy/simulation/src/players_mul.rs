@@ -1,10 +1,12 @@
 use std::collections::HashSet;
+use std::time::Instant;
 
 use anna_eval::Eval;
 use anna_model::{cards::Card, ActionClass, Money};
 
 use players::*;
 
+use engine::Score;
 use Event;
 use SeatId;
 
@@ -61,6 +63,38 @@ pub trait PlayersMul<E> {
       events,
     )
   }
+
+  fn mul_policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.select(seat_id).policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  // All players are notified, the game being over for every seat.
+  fn mul_game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    for (players, _) in self.select_all() {
+      players.game_end(score, showdown);
+    }
+  }
+
+  fn mul_deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.select(seat_id).deadline(seat_id, deadline)
+  }
 }
 
 #[derive(Clone)]
@@ -136,6 +170,35 @@ where
       events,
     )
   }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.mul_policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.mul_game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.mul_deadline(seat_id, deadline)
+  }
 }
 
 #[derive(Clone)]
@@ -196,6 +259,35 @@ where
       events,
     )
   }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.mul_policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.mul_game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.mul_deadline(seat_id, deadline)
+  }
 }
 
 #[derive(Clone)]
@@ -269,4 +361,33 @@ where
       events,
     )
   }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.mul_policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.mul_game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.mul_deadline(seat_id, deadline)
+  }
 }
@@ -0,0 +1,232 @@
+use serde_json;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use anna_model::{cards::Card, Money};
+use engine::Score;
+use players::Players;
+use Event;
+use SeatId;
+
+/** Recorded call of `Players`, one JSON object per line.
+ **
+ **   {"type":"init","blinds":[50,100],"player_first":1,"player_funds":[10000,10000],
+ **    "playing_hands":[[0,[12,25]]]}
+ **   {"type":"play","round_id":3,"table_target":400,"table_target_raise":200,
+ **    "player_pots":[400,400],"seat_id":0,"player_fund":9600,"events":[...],"action":2}
+ **
+ ** `action` is missing when the play failed.
+ **/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+  Init {
+    blinds: Vec<Money>,
+    player_first: SeatId,
+    player_funds: Vec<Money>,
+    playing_hands: Vec<(SeatId, Vec<Card>)>,
+  },
+  Play {
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: Vec<Money>,
+    seat_id: SeatId,
+    player_fund: Money,
+    events: Vec<Event>,
+    action: Option<u8>,
+  },
+  GameEnd {
+    score: Score,
+    showdown: Vec<(SeatId, Vec<Card>)>,
+  },
+}
+
+/** Players recording every call they get, along with the actions played.
+ **
+ ** Records are flushed line by line, a file being complete up to the last decision even when the
+ ** process is killed. Errors are logged and kept in `error`, the players playing on.
+ **/
+pub struct PlayersRecorder<P, W: Write> {
+  pub players: P,
+  writer: W,
+  pub error: Option<io::Error>,
+}
+
+impl<P> PlayersRecorder<P, BufWriter<File>> {
+  pub fn create(players: P, path: &Path) -> io::Result<PlayersRecorder<P, BufWriter<File>>> {
+    let file = File::create(path)?;
+    Ok(PlayersRecorder::new(players, BufWriter::new(file)))
+  }
+}
+
+impl<P, W: Write> PlayersRecorder<P, W> {
+  pub fn new(players: P, writer: W) -> PlayersRecorder<P, W> {
+    PlayersRecorder { players: players, writer: writer, error: None }
+  }
+
+  fn record(&mut self, record: &Record) -> () {
+    let result = serde_json::to_string(record)
+      .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+      .and_then(|line| {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+      });
+
+    if let Err(e) = result {
+      error!("Players recorder: {}", e);
+      self.error = Some(e);
+    }
+  }
+}
+
+impl<P: Players<E>, W: Write, E> Players<E> for PlayersRecorder<P, W> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.record(&Record::Init {
+      blinds: blinds.to_vec(),
+      player_first: player_first,
+      player_funds: player_funds.to_vec(),
+      playing_hands: playing_hands.clone(),
+    });
+
+    self.players.init(blinds, player_first, player_funds, playing_hands)
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, E> {
+    let result = self.players.play(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    );
+
+    self.record(&Record::Play {
+      round_id: round_id,
+      table_target: table_target,
+      table_target_raise: table_target_raise,
+      player_pots: player_pots.to_vec(),
+      seat_id: seat_id,
+      player_fund: player_fund,
+      events: events.to_vec(),
+      action: result.as_ref().ok().cloned(),
+    });
+
+    result
+  }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.players.policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    self.record(&Record::GameEnd { score: score.clone(), showdown: showdown.to_vec() });
+
+    self.players.game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: SeatId, deadline: Option<Instant>) -> () {
+    self.players.deadline(seat_id, deadline)
+  }
+}
+
+pub fn records_load(path: &Path) -> io::Result<Vec<Record>> {
+  let reader = BufReader::new(File::open(path)?);
+
+  let mut records = Vec::new();
+  for line in reader.lines() {
+    let line = line?;
+    if line.trim().is_empty() {
+      continue;
+    }
+    records.push(
+      serde_json::from_str(&line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+    );
+  }
+
+  Ok(records)
+}
+
+/** Feed recorded calls to other players.
+ **
+ ** Returns every play as recorded along with the action of the players, `None` for a failed one.
+ **/
+pub fn records_replay<E, P: Players<E>>(
+  records: &[Record],
+  players: &mut P,
+) -> Vec<(Option<u8>, Option<u8>)> {
+  let mut plays = Vec::new();
+
+  for record in records {
+    match record {
+      &Record::Init { ref blinds, player_first, ref player_funds, ref playing_hands } => {
+        players.init(blinds, player_first, player_funds, playing_hands);
+      }
+      &Record::Play {
+        round_id,
+        table_target,
+        table_target_raise,
+        ref player_pots,
+        seat_id,
+        player_fund,
+        ref events,
+        action,
+      } => {
+        let replayed = players
+          .play(
+            round_id,
+            table_target,
+            table_target_raise,
+            player_pots,
+            seat_id,
+            player_fund,
+            events,
+          )
+          .ok();
+        plays.push((action, replayed));
+      }
+      &Record::GameEnd { ref score, ref showdown } => {
+        players.game_end(score, showdown);
+      }
+    }
+  }
+
+  plays
+}
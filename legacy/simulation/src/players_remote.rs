@@ -0,0 +1,350 @@
+use serde_json;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anna_model::{cards::Card, Action, ActionClass, Money};
+use engine::{Pot, Score};
+use players::Players;
+use Event;
+use SeatId;
+
+/** Remote protocol
+ **
+ ** Line-delimited JSON, every message is a single line. The table sends `Message`s, the client
+ ** answers each `observation` with a `Reply`. Cards are encoded as `u8` (`Card::as_u8`) and money
+ ** in cents.
+ **
+ **   > {"type":"init","blinds":[100,100],"player_first":0,"player_funds":[10000,10000],
+ **    "playing_hands":[[1,[11]]]}
+ **   > {"type":"observation","round_id":0,"table_target":100,"table_target_raise":100,
+ **    "player_pots":[100,100],"seat_id":1,"player_fund":9900,
+ **    "events":[{"Play":{"seat_id":0,"action":1}}],"legal_actions":[1,2]}
+ **   < {"action":2}
+ **   > {"type":"game_end","player_funds":[[0,9800],[1,10200]],"pot":400,
+ **    "pots":[{"amount":400,"seats":[0,1],"winners":[1],"awards":[[1,400]]}],"winners":[1],
+ **    "winners_score":2.0,"showdown":[[0,[10]],[1,[11]]]}
+ **/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+  Init {
+    blinds: Vec<Money>,
+    player_first: SeatId,
+    player_funds: Vec<Money>,
+    playing_hands: Vec<(SeatId, Vec<Card>)>,
+  },
+  Observation {
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: Vec<Money>,
+    seat_id: SeatId,
+    player_fund: Money,
+    events: Vec<Event>,
+    legal_actions: Vec<u8>,
+  },
+  GameEnd {
+    player_funds: Vec<(SeatId, Money)>,
+    pot: Money,
+    pots: Vec<Pot>,
+    winners: Vec<SeatId>,
+    winners_score: f32,
+    showdown: Vec<(SeatId, Vec<Card>)>,
+  },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Reply {
+  pub action: u8,
+}
+
+fn message_send<W: Write, M: ::serde::Serialize>(writer: &mut W, message: &M) -> io::Result<()> {
+  let line = serde_json::to_string(message).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+  writer.write_all(line.as_bytes())?;
+  writer.write_all(b"\n")?;
+  writer.flush()
+}
+
+// The bytes of a line are kept in `line` when a read times out, to be completed by the next call.
+fn message_recv<R: BufRead, M>(reader: &mut R, line: &mut Vec<u8>) -> io::Result<Option<M>>
+where
+  for<'de> M: ::serde::Deserialize<'de>,
+{
+  if reader.read_until(b'\n', line)? == 0 {
+    return Ok(None);
+  }
+  let message = serde_json::from_slice(line);
+  line.clear();
+  message.map(Some).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// Streams whose reads can time out.
+pub trait ReadTimeout {
+  fn read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl ReadTimeout for TcpStream {
+  fn read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+    self.set_read_timeout(timeout)
+  }
+}
+
+impl ReadTimeout for UnixStream {
+  fn read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+    self.set_read_timeout(timeout)
+  }
+}
+
+/** Players living out of process, reached through a stream (TCP or Unix socket).
+ **
+ ** Errors are logged and kept in `error`, `play` failing with `Err(())` like other players. Past
+ ** the deadline of a decision the client is not waited for, the check or fold the engine plays
+ ** for it being returned and its late reply skipped.
+ **/
+pub struct PlayersRemote<'a, S: Read + Write> {
+  action_class: &'a ActionClass,
+  blind_biggest: Money,
+  stream: BufReader<S>,
+  line: Vec<u8>, // Line read so far, across the reads timing out.
+  deadline: Option<Instant>,
+  replies_late: usize, // Replies to skip, of the decisions given up.
+  pub error: Option<io::Error>,
+}
+
+impl<'a> PlayersRemote<'a, TcpStream> {
+  // Wait for a single client to connect.
+  pub fn tcp_accept(
+    listener: &TcpListener,
+    action_class: &'a ActionClass,
+  ) -> io::Result<PlayersRemote<'a, TcpStream>> {
+    let (stream, _) = listener.accept()?;
+    stream.set_nodelay(true)?;
+    Ok(PlayersRemote::new(stream, action_class))
+  }
+}
+
+impl<'a> PlayersRemote<'a, UnixStream> {
+  pub fn unix_accept(
+    listener: &UnixListener,
+    action_class: &'a ActionClass,
+  ) -> io::Result<PlayersRemote<'a, UnixStream>> {
+    let (stream, _) = listener.accept()?;
+    Ok(PlayersRemote::new(stream, action_class))
+  }
+}
+
+impl<'a, S: Read + Write> PlayersRemote<'a, S> {
+  pub fn new(stream: S, action_class: &'a ActionClass) -> PlayersRemote<'a, S> {
+    PlayersRemote {
+      action_class: action_class,
+      blind_biggest: Money::zero(),
+      stream: BufReader::new(stream),
+      line: Vec::new(),
+      deadline: None,
+      replies_late: 0,
+      error: None,
+    }
+  }
+
+  fn error_keep<A>(&mut self, result: io::Result<A>) -> Option<A> {
+    match result {
+      Ok(a) => Some(a),
+      Err(e) => {
+        error!("Remote player: {}", e);
+        self.error = Some(e);
+        None
+      }
+    }
+  }
+}
+
+impl<'a, S: Read + Write + ReadTimeout> Players<()> for PlayersRemote<'a, S> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    player_funds: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.blind_biggest = *blinds.iter().max().unwrap();
+
+    let message = Message::Init {
+      blinds: blinds.to_vec(),
+      player_first: player_first,
+      player_funds: player_funds.to_vec(),
+      playing_hands: playing_hands.clone(),
+    };
+    let result = message_send(self.stream.get_mut(), &message);
+    self.error_keep(result);
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    let mask = self.action_class.normalize(
+      self.blind_biggest,
+      round_id,
+      table_target,
+      table_target_raise,
+      player_fund,
+      player_pots[seat_id],
+    );
+
+    let legal_actions: Vec<u8> =
+      (0..self.action_class.size()).filter(|i| mask.contains(i)).map(|i| i as u8).collect();
+
+    let message = Message::Observation {
+      round_id: round_id,
+      table_target: table_target,
+      table_target_raise: table_target_raise,
+      player_pots: player_pots.to_vec(),
+      seat_id: seat_id,
+      player_fund: player_fund,
+      events: events.to_vec(),
+      legal_actions: legal_actions.clone(),
+    };
+
+    let result = message_send(self.stream.get_mut(), &message).and_then(|_| loop {
+      // Reads time out at the deadline, a zero timeout being rejected.
+      let timeout = self.deadline.map(|deadline| {
+        let now = Instant::now();
+        if deadline > now {
+          deadline - now
+        } else {
+          Duration::from_millis(1)
+        }
+      });
+      self.stream.get_ref().read_timeout(timeout)?;
+
+      let reply = message_recv::<_, Reply>(&mut self.stream, &mut self.line);
+      if self.replies_late > 0 {
+        match reply {
+          Ok(Some(_)) => {
+            self.replies_late -= 1;
+            continue;
+          }
+          _ => {}
+        }
+      }
+
+      return match reply {
+        Err(ref e)
+          if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut =>
+        {
+          Ok(None)
+        }
+        Err(e) => Err(e),
+        Ok(None) => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed.")),
+        Ok(Some(Reply { action })) if legal_actions.contains(&action) => Ok(Some(action)),
+        Ok(Some(Reply { action })) => Err(io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("Illegal action {}, expected one of {:?}.", action, legal_actions),
+        )),
+      };
+    });
+
+    match self.error_keep(result).ok_or(())? {
+      Some(action) => Ok(action),
+      None => {
+        warn!("Remote player: no reply by the deadline, seat {}.", seat_id);
+        self.replies_late += 1;
+
+        let action = if player_pots[seat_id] == table_target { Action::Call } else { Action::Fold };
+        Ok(self.action_class.apply(self.blind_biggest, player_fund, action, Money::zero()))
+      }
+    }
+  }
+
+  fn deadline(&mut self, _: SeatId, deadline: Option<Instant>) -> () {
+    self.deadline = deadline;
+  }
+
+  // Tell the client the outcome of the game.
+  fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+    let message = Message::GameEnd {
+      player_funds: score.player_funds.clone(),
+      pot: score.pot,
+      pots: score.pots.clone(),
+      winners: score.winners.clone(),
+      winners_score: score.winners_score,
+      showdown: showdown.to_vec(),
+    };
+    let result = message_send(self.stream.get_mut(), &message);
+    self.error_keep(result);
+  }
+}
+
+/** Reference client
+ **
+ ** Serve a local `Players` through the remote protocol until the table closes the connection.
+ **/
+pub fn client_serve<S: Read + Write, E, P: Players<E>>(
+  stream: S,
+  players: &mut P,
+) -> io::Result<()> {
+  let mut stream = BufReader::new(stream);
+  let mut line = Vec::new();
+
+  while let Some(message) = message_recv::<_, Message>(&mut stream, &mut line)? {
+    match message {
+      Message::Init { blinds, player_first, player_funds, playing_hands } => {
+        players.init(&blinds, player_first, &player_funds, &playing_hands);
+      }
+      Message::Observation {
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        events,
+        ..
+      } => {
+        let action = players
+          .play(
+            round_id,
+            table_target,
+            table_target_raise,
+            &player_pots,
+            seat_id,
+            player_fund,
+            &events,
+          )
+          .map_err(|_| io::Error::new(io::ErrorKind::Other, "Player failed to play."))?;
+        message_send(stream.get_mut(), &Reply { action: action })?;
+      }
+      Message::GameEnd { player_funds, pot, pots, winners, winners_score, showdown } => {
+        let score = Score {
+          player_funds: player_funds,
+          pot: pot,
+          pots: pots,
+          winners: winners,
+          winners_score: winners_score,
+        };
+        players.game_end(&score, &showdown);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+pub fn client_tcp<A: ToSocketAddrs>(addr: A) -> io::Result<TcpStream> {
+  let stream = TcpStream::connect(addr)?;
+  stream.set_nodelay(true)?;
+  Ok(stream)
+}
+
+pub fn client_unix<P: AsRef<Path>>(path: P) -> io::Result<UnixStream> {
+  UnixStream::connect(path)
+}
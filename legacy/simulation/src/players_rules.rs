@@ -0,0 +1,313 @@
+use rand::Rng;
+use std::collections::HashMap;
+
+use anna_eval::Eval;
+use anna_model::{
+  cards::{Card, CardVal},
+  profile::Profile,
+  ActionClass, Money,
+};
+use players::Players;
+use Event;
+use SeatId;
+
+/** Style of a rule-based player
+ **
+ ** Hands are classed by return rate (strength over pot odds) with `thresholds`, as weak,
+ ** marginal, good or strong, and played (fold, call, raise) with:
+ **
+ **   weak:     `bluff` raise, `loose` call
+ **   marginal: `bluff` raise, (1 + `loose`) / 2 call
+ **   good:     `aggression` raise, call otherwise
+ **   strong:   (1 + `aggression`) / 2 raise, call otherwise
+ **
+ ** Preflop two cards hands scoring less than `chart` (Chen formula) are folded. Late positions
+ ** play looser, the return rate being raised and the chart lowered by up to `position` on the
+ ** button.
+ **/
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+  pub thresholds: [f32; 3],
+  pub aggression: f32,
+  pub bluff: f32,
+  pub loose: f32,
+  pub chart: f32,
+  pub position: f32,
+}
+
+pub const STYLES: [&str; 6] = ["lex", "tag", "lag", "calling_station", "maniac", "nit"];
+
+impl Style {
+  // Close to the `Lex` table.
+  pub fn lex() -> Style {
+    Style {
+      thresholds: [0.8, 1.0, 1.3],
+      aggression: 0.4,
+      bluff: 0.05,
+      loose: 0.,
+      chart: 0.,
+      position: 0.,
+    }
+  }
+
+  // Tight aggressive.
+  pub fn tag() -> Style {
+    Style {
+      thresholds: [0.9, 1.1, 1.4],
+      aggression: 0.6,
+      bluff: 0.05,
+      loose: 0.05,
+      chart: 8.,
+      position: 0.25,
+    }
+  }
+
+  // Loose aggressive.
+  pub fn lag() -> Style {
+    Style {
+      thresholds: [0.7, 0.9, 1.2],
+      aggression: 0.7,
+      bluff: 0.15,
+      loose: 0.15,
+      chart: 5.,
+      position: 0.3,
+    }
+  }
+
+  // Calls everything, rarely raises.
+  pub fn calling_station() -> Style {
+    Style {
+      thresholds: [0.8, 1.0, 1.3],
+      aggression: 0.1,
+      bluff: 0.,
+      loose: 0.8,
+      chart: 0.,
+      position: 0.,
+    }
+  }
+
+  // Raises everything.
+  pub fn maniac() -> Style {
+    Style {
+      thresholds: [0.5, 0.7, 0.9],
+      aggression: 0.9,
+      bluff: 0.5,
+      loose: 0.3,
+      chart: 0.,
+      position: 0.,
+    }
+  }
+
+  // Plays premium hands only, passively.
+  pub fn nit() -> Style {
+    Style {
+      thresholds: [1.0, 1.3, 1.8],
+      aggression: 0.3,
+      bluff: 0.,
+      loose: 0.,
+      chart: 10.,
+      position: 0.,
+    }
+  }
+
+  pub fn preset(name: &str) -> Option<Style> {
+    match name {
+      "lex" => Some(Style::lex()),
+      "tag" => Some(Style::tag()),
+      "lag" => Some(Style::lag()),
+      "calling_station" => Some(Style::calling_station()),
+      "maniac" => Some(Style::maniac()),
+      "nit" => Some(Style::nit()),
+      _ => None,
+    }
+  }
+
+  // Probabilities of fold, call and raise for a return rate.
+  pub fn probs(&self, return_rate: f32) -> Vec<f32> {
+    let (call, raise) = if return_rate < self.thresholds[0] {
+      (self.loose, self.bluff)
+    } else if return_rate < self.thresholds[1] {
+      ((1. + self.loose) / 2., self.bluff)
+    } else if return_rate < self.thresholds[2] {
+      (1. - self.aggression, self.aggression)
+    } else {
+      let raise = (1. + self.aggression) / 2.;
+      (1. - raise, raise)
+    };
+
+    // Continuing at most always.
+    let total = (call + raise).max(1.);
+    vec![1. - (call + raise) / total, call / total, raise / total]
+  }
+}
+
+/** Chen formula, strength of a two cards hand from -1 (72o) to 20 (AA).
+ **
+ ** ref: https://en.wikipedia.org/wiki/Texas_hold_%27em_starting_hands#Chen_formula
+ **/
+pub fn chen(hand: &[Card]) -> f32 {
+  fn points(value: CardVal) -> f32 {
+    match value {
+      CardVal::CA => 10.,
+      CardVal::CK => 8.,
+      CardVal::CQ => 7.,
+      CardVal::CJ => 6.,
+      value => (value as usize + 2) as f32 / 2.,
+    }
+  }
+
+  let (high, low) =
+    if hand[0].value >= hand[1].value { (hand[0], hand[1]) } else { (hand[1], hand[0]) };
+
+  if high.value == low.value {
+    return (2. * points(high.value)).max(5.);
+  }
+
+  let mut score = points(high.value);
+
+  if high.suit == low.suit {
+    score += 2.;
+  }
+
+  let gap = high.value as usize - low.value as usize - 1;
+  score -= match gap {
+    0 => 0.,
+    1 => 1.,
+    2 => 2.,
+    3 => 4.,
+    _ => 5.,
+  };
+
+  if gap <= 1 && high.value < CardVal::CQ {
+    score += 1.;
+  }
+
+  score.ceil()
+}
+
+/** Rule-based players, parameterized by a `Style`.
+ **
+ ** Actions are classed as `ActionLimit` (fold, call, raise), illegal ones being played as call.
+ **/
+#[derive(Clone)]
+pub struct PlayersRules<'a, R: 'a> {
+  rng: R,
+  accuracy: usize,
+  action_class: &'a ActionClass,
+  eval: &'a Eval,
+  profile: &'a Profile,
+  pub style: Style,
+  blind_biggest: Money,
+  player_first: SeatId,
+  players_cards: HashMap<SeatId, Vec<Card>>,
+  table_cards: Vec<Card>,
+}
+
+impl<'a, R: Rng> PlayersRules<'a, R> {
+  pub fn new(
+    rng: R,
+    accuracy: usize,
+    ac: &'a ActionClass,
+    eval: &'a Eval,
+    profile: &'a Profile,
+    style: Style,
+  ) -> PlayersRules<'a, R> {
+    PlayersRules {
+      rng: rng,
+      accuracy: accuracy,
+      action_class: ac,
+      eval: eval,
+      profile: profile,
+      style: style,
+      blind_biggest: Money::zero(),
+      player_first: 0,
+      players_cards: HashMap::new(),
+      table_cards: Vec::new(),
+    }
+  }
+
+  // From 0 for the first player to 1 on the button.
+  fn lateness(&self, seat_id: SeatId) -> f32 {
+    let players = self.profile.players;
+    let position = (seat_id + players - self.player_first) % players;
+    position as f32 / (players - 1).max(1) as f32
+  }
+}
+
+impl<'a, R: Rng> Players<()> for PlayersRules<'a, R> {
+  fn init(
+    &mut self,
+    blinds: &[Money],
+    player_first: SeatId,
+    _: &[Money],
+    playing_hands: &Vec<(usize, Vec<Card>)>,
+  ) -> () {
+    self.blind_biggest = *blinds.iter().max().unwrap();
+    self.player_first = player_first;
+
+    for &(seat_id, ref cards) in playing_hands {
+      self.players_cards.insert(seat_id, cards.clone());
+    }
+
+    self.table_cards = Vec::new();
+  }
+
+  fn play(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Result<u8, ()> {
+    use anna_eval::strength::{return_rate, strength};
+    use anna_utils::math;
+
+    for event in events {
+      match event {
+        &Event::Table { ref cards } => {
+          self.table_cards.extend(cards.iter());
+        }
+        _ => {}
+      }
+    }
+
+    let hand = self.players_cards.get(&seat_id).ok_or(())?.clone();
+    let target = Money::from_i32(table_target - player_pots[seat_id]).unwrap_or(Money::zero());
+    let late = self.style.position * self.lateness(seat_id);
+
+    let probs = if round_id == 0 && hand.len() == 2 && chen(&hand) < self.style.chart * (1. - late)
+    {
+      vec![1., 0., 0.]
+    } else {
+      let strength =
+        strength(&mut self.rng, self.eval, self.profile, self.accuracy, &hand, &self.table_cards);
+      let raise = table_target_raise.unwrap_or(Money::zero());
+      let return_rate = return_rate(strength, player_pots, target + raise);
+
+      self.style.probs(return_rate * (1. + late))
+    };
+
+    let mask = self.action_class.normalize(
+      self.blind_biggest,
+      round_id,
+      table_target,
+      table_target_raise,
+      player_fund,
+      player_pots[seat_id],
+    );
+
+    let action_i = math::sample(&mut self.rng, probs);
+
+    // Checking rather than folding for free.
+    if action_i == 0 && target == Money::zero() {
+      Ok(1)
+    } else if !mask.contains(&action_i) {
+      Ok(1)
+    } else {
+      Ok(action_i as u8)
+    }
+  }
+}
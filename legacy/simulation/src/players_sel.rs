@@ -1,6 +1,8 @@
 use anna_model::{cards::Card, Money};
+use engine::Score;
 use players::Players;
 use std::collections::HashSet;
+use std::time::Instant;
 use Event;
 
 /*
@@ -95,4 +97,33 @@ where
       events,
     )
   }
+
+  fn policy(
+    &mut self,
+    round_id: usize,
+    table_target: Money,
+    table_target_raise: Option<Money>,
+    player_pots: &[Money],
+    seat_id: usize,
+    player_fund: Money,
+    events: &[Event],
+  ) -> Vec<f32> {
+    self.players[self.players_i].policy(
+      round_id,
+      table_target,
+      table_target_raise,
+      player_pots,
+      seat_id,
+      player_fund,
+      events,
+    )
+  }
+
+  fn game_end(&mut self, score: &Score, showdown: &[(usize, Vec<Card>)]) -> () {
+    self.players[self.players_i].game_end(score, showdown)
+  }
+
+  fn deadline(&mut self, seat_id: usize, deadline: Option<Instant>) -> () {
+    self.players[self.players_i].deadline(seat_id, deadline)
+  }
 }
@@ -1,6 +1,7 @@
 extern crate anna_model;
 
-use anna_model::{Action, Money};
+use anna_model::{cards::Card, Action, Money};
+use engine::Error;
 use Act;
 use SeatId;
 use Sim;
@@ -73,14 +74,36 @@ pub fn pledge_apply(
   }
 }
 
-pub fn pledge_unapply(
+pub fn pledge_unapply<E>(
   player_funds: &mut Vec<Money>,
   player_pots: &mut Vec<Money>,
   seat_id: usize,
   money: Money,
-) -> () {
-  player_pots[seat_id] = Money::from_i32(player_pots[seat_id] - money).unwrap();
-  player_funds[seat_id] = player_funds[seat_id] + money;
+) -> Result<(), Error<E>> {
+  let delta = player_pots[seat_id] - money;
+  if delta >= 0 {
+    player_pots[seat_id] = Money::from_i32(delta).unwrap();
+    player_funds[seat_id] = player_funds[seat_id] + money;
+    Ok(())
+  } else {
+    Err(Error::InsufficientPot { seat_id: seat_id, pot: player_pots[seat_id], unpledge: money })
+  }
+}
+
+// Extract the table cards revealed at the start of a given round.
+pub fn round_cards(rounds: &[usize], table_cards: &[Card], round_id: usize) -> Vec<Card> {
+  let mut skips = 0;
+  let mut takes = 0;
+
+  for i in 1..(round_id + 1) {
+    if round_id == i {
+      takes = rounds[i];
+    } else {
+      skips += rounds[i];
+    }
+  }
+
+  table_cards.iter().skip(skips).take(takes).map(|&c| c).collect()
 }
 
 pub fn seat_next(players: usize, seat_id: SeatId) -> SeatId {
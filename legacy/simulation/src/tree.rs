@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anna_eval::Eval;
+use anna_model::{cards::Card, Money};
+use anna_utils::bincode;
+use game::{Error, GameState};
+use SeatId;
+use Sim;
+
+pub type NodeId = usize;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Node {
+  // Cards dealt (private hands by seat at the root, table cards after), probability and child.
+  Chance { outcomes: Vec<(Vec<Card>, f32, NodeId)> },
+  Decision { seat_id: SeatId, infoset: String, actions: Vec<(u8, NodeId)> },
+  Terminal { payoffs: Vec<(SeatId, i32)> },
+}
+
+/** Game Tree
+ **
+ ** Every chance and decision node of a small profile (Kuhn, Leduc), nodes are stored in a flat
+ ** vector and reference their children by index.
+ **/
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tree {
+  pub players: usize,
+  pub root: NodeId,
+  pub nodes: Vec<Node>,
+}
+
+impl Tree {
+  pub fn build(
+    sim: &Sim,
+    evaluator: &Eval,
+    player_funds: &Vec<Money>,
+    player_first: SeatId,
+  ) -> Result<Tree, Error> {
+    let deals = tree_deals(sim, &sim.profile.deck, sim.profile.players);
+    let p = 1. / deals.len() as f32;
+
+    let mut nodes = Vec::new();
+    let mut outcomes = Vec::new();
+
+    for hands in deals.iter() {
+      let players_init: Vec<(SeatId, Money, Vec<Card>)> = hands
+        .iter()
+        .enumerate()
+        .map(|(seat_id, cards)| (seat_id, player_funds[seat_id], cards.clone()))
+        .collect();
+
+      let state = GameState::new(sim, evaluator, &players_init, &Vec::new(), player_first)?;
+      let child = tree_expand(&mut nodes, &state)?;
+
+      outcomes.push((hands.concat(), p, child));
+    }
+
+    nodes.push(Node::Chance { outcomes: outcomes });
+
+    Ok(Tree { players: sim.profile.players, root: nodes.len() - 1, nodes: nodes })
+  }
+
+  pub fn load(path: &Path) -> Result<Tree, bincode::Error> {
+    bincode::deserialize_from_file(path)
+  }
+
+  pub fn save(&self, path: &Path) -> Result<(), bincode::Error> {
+    bincode::serialize_into_file(path, self)
+  }
+
+  pub fn children(&self, node_id: NodeId) -> Vec<NodeId> {
+    match &self.nodes[node_id] {
+      &Node::Chance { ref outcomes } => outcomes.iter().map(|&(_, _, c)| c).collect(),
+      &Node::Decision { ref actions, .. } => actions.iter().map(|&(_, c)| c).collect(),
+      &Node::Terminal { .. } => Vec::new(),
+    }
+  }
+
+  // Depth-first traversal from the root, parents are visited before their children.
+  pub fn walk<F>(&self, mut f: F) -> ()
+  where
+    F: FnMut(NodeId, &Node, usize) -> (),
+  {
+    let mut stack = vec![(self.root, 0)];
+
+    while let Some((node_id, depth)) = stack.pop() {
+      f(node_id, &self.nodes[node_id], depth);
+      for child in self.children(node_id).into_iter().rev() {
+        stack.push((child, depth + 1));
+      }
+    }
+  }
+
+  // Decision nodes grouped by information set.
+  pub fn infosets(&self) -> HashMap<String, Vec<NodeId>> {
+    let mut infosets: HashMap<String, Vec<NodeId>> = HashMap::new();
+
+    for (node_id, node) in self.nodes.iter().enumerate() {
+      if let &Node::Decision { ref infoset, .. } = node {
+        infosets.entry(infoset.clone()).or_insert_with(Vec::new).push(node_id);
+      }
+    }
+
+    infosets
+  }
+}
+
+// Private hands for every seat, in all possible orders.
+pub fn tree_deals(sim: &Sim, deck: &Vec<Card>, players: usize) -> Vec<Vec<Vec<Card>>> {
+  use anna_utils::math::combinations;
+
+  if players == 0 {
+    return vec![Vec::new()];
+  }
+
+  let size = sim.profile.rounds[0];
+  let mut deals = Vec::new();
+
+  for xs in combinations(deck.len(), size) {
+    let hand: Vec<Card> = xs.iter().map(|&i| deck[i]).collect();
+    let deck_rest: Vec<Card> = deck.iter().filter(|c| !hand.contains(c)).cloned().collect();
+
+    for mut rest in tree_deals(sim, &deck_rest, players - 1) {
+      rest.insert(0, hand.clone());
+      deals.push(rest);
+    }
+  }
+
+  deals
+}
+
+fn tree_expand(nodes: &mut Vec<Node>, state: &GameState) -> Result<NodeId, Error> {
+  let node = if state.is_terminal() {
+    Node::Terminal { payoffs: state.payoffs().unwrap() }
+  } else if state.is_chance() {
+    let outcomes = state.chance_outcomes();
+    let p = 1. / outcomes.len() as f32;
+
+    let mut children = Vec::new();
+    for cards in outcomes {
+      let child = tree_expand(nodes, &state.apply_chance(&cards)?)?;
+      children.push((cards, p, child));
+    }
+
+    Node::Chance { outcomes: children }
+  } else {
+    let seat_id = state.current_player().unwrap();
+
+    let mut actions = Vec::new();
+    for action_i in state.legal_actions() {
+      let child = tree_expand(nodes, &state.apply(action_i)?)?;
+      actions.push((action_i, child));
+    }
+
+    Node::Decision { seat_id: seat_id, infoset: state.infoset_key(seat_id), actions: actions }
+  };
+
+  nodes.push(node);
+  Ok(nodes.len() - 1)
+}
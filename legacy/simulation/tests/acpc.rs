@@ -0,0 +1,215 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+const HOLDEM_LIMIT_2P_REVERSE_BLINDS: &str = "GAMEDEF
+limit
+numPlayers = 2
+numRounds = 4
+blind = 10 5
+raiseSize = 10 10 20 20
+firstPlayer = 2 1 1 1
+maxRaises = 3 4 4 4
+numSuits = 4
+numRanks = 13
+numHoleCards = 2
+numBoardCards = 0 3 1 1
+END GAMEDEF
+";
+
+#[test]
+fn acpc_match_state() {
+  use anna_model::{
+    cards::{Card, CardVal, Suit},
+    Action,
+  };
+  use anna_simulation::acpc::{card_parse, Game, MatchState};
+
+  assert!(card_parse("Ts") == Some(Card { value: CardVal::C10, suit: Suit::Spade }));
+  assert!(card_parse("1s") == None);
+
+  let s = "MATCHSTATE:0:30:cc/r:9s8h|/8c8d5c";
+  let state = MatchState::parse(s).unwrap();
+  assert!(state.position == 0 && state.hand == 30);
+  assert!(state.betting == vec![vec![Action::Call, Action::Call], vec![Action::Raise]]);
+  assert!(state.hole_cards[1].is_empty());
+  assert!(state.board_cards.len() == 1 && state.board_cards[0].len() == 3);
+  assert!(state.to_string() == s);
+
+  // Replies of clients.
+  assert!(
+    MatchState::parse("MATCHSTATE:1:0::|Ah2c:c").unwrap().to_string() == "MATCHSTATE:1:0::|Ah2c"
+  );
+
+  let game = Game::parse(HOLDEM_LIMIT_2P_REVERSE_BLINDS).unwrap();
+  assert!(Game::parse(&game.to_string()).unwrap() == game);
+
+  // The small blind acts first preflop, the big blind first after.
+  let betting = |s: &str| game.betting(&MatchState::parse(s).unwrap().betting).unwrap();
+  assert!(betting("MATCHSTATE:1:0::|Ah2c").acting == Some(1));
+  assert!(betting("MATCHSTATE:1:0:r:|Ah2c").acting == Some(0));
+  assert!(betting("MATCHSTATE:1:0:rc/:|Ah2c/8c8d5c").acting == Some(0));
+  assert!(betting("MATCHSTATE:1:0:rc/r:|Ah2c/8c8d5c").acting == Some(1));
+  assert!(betting("MATCHSTATE:1:0:rc/rf:|Ah2c/8c8d5c").acting == None);
+  assert!(betting("MATCHSTATE:1:0:rc/cc/cc/cc:|Ah2c/8c8d5c/Kd/2s").acting == None);
+
+  let xs = betting("MATCHSTATE:0:0:rrrc/c:Ah2c|/8c8d5c");
+  assert!(xs.round_id == 1 && xs.raises == 0 && xs.acting == Some(1));
+  assert!(xs.pots.iter().all(|m| m.unpack() == 40));
+
+  assert!(game.betting(&MatchState::parse("MATCHSTATE:0:0:rrrr:Ah2c|").unwrap().betting).is_err());
+}
+
+#[test]
+fn acpc_dealer_client() {
+  use rand::{SeedableRng, StdRng};
+  use std::net::{TcpListener, TcpStream};
+  use std::thread;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_texas_limit, Money};
+  use anna_simulation::{
+    acpc::{client_run, Game, PlayersAcpc},
+    perf::run_benchmark,
+    players::PlayersRand,
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::texas();
+
+  let blind_big = Money::new(1, 0);
+  let profile = profile_texas_limit(2, blind_big / 2, blind_big);
+
+  let ref action_class = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind_big,
+    profile: profile,
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let game = Game::from_sim(sim);
+  assert!(Game::parse(&game.to_string()).unwrap() == game);
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  let clients: Vec<_> = (0..2)
+    .map(|i| {
+      let game = game.clone();
+      let stream = TcpStream::connect(addr).unwrap();
+      thread::spawn(move || {
+        let ref action_class = ActionLimit { raises: vec![1, 1, 2, 2] };
+        let rng: StdRng = SeedableRng::from_seed([i; 32]);
+        let mut players = PlayersRand::new(rng, action_class, Money::new(1, 0));
+        client_run(stream, &game, action_class, Money::new(200, 0), &mut players)
+      })
+    })
+    .collect();
+
+  let mut players = PlayersAcpc::tcp_accept(&listener, action_class, 2).unwrap();
+  let funds = vec![(0, Money::new(200, 0)), (1, Money::new(200, 0))];
+  let rates = run_benchmark(sim, eval, &funds, 50, &mut players);
+  assert!(rates.len() == 2);
+  assert!(players.error.is_none());
+
+  drop(players);
+  for client in clients {
+    let hands = client.join().unwrap().unwrap();
+    assert!(hands > 0 && hands <= 50);
+  }
+}
+
+#[test]
+fn acpc_dealer_broadcast() {
+  use rand::{SeedableRng, StdRng};
+  use std::io::{BufRead, BufReader, Write};
+  use std::net::{TcpListener, TcpStream};
+  use std::thread;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_texas_limit, Money};
+  use anna_simulation::{
+    acpc::{Game, MatchState, PlayersAcpc},
+    engine::table_game_simulate,
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::texas();
+
+  let blind_big = Money::new(1, 0);
+  let profile = profile_texas_limit(2, blind_big / 2, blind_big);
+
+  let ref action_class = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind_big,
+    profile: profile,
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let game = Game::from_sim(sim);
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  // Clients calling down, keeping every state received.
+  let clients: Vec<_> = (0..2)
+    .map(|_| {
+      let game = game.clone();
+      let stream = TcpStream::connect(addr).unwrap();
+      thread::spawn(move || {
+        let mut stream = BufReader::new(stream);
+        stream.get_mut().write_all(b"VERSION:2.0.0\r\n").unwrap();
+
+        let mut states = Vec::new();
+        let mut line = String::new();
+        while stream.read_line(&mut line).unwrap() > 0 {
+          let state = MatchState::parse(line.trim_end()).unwrap();
+          if game.betting(&state.betting).unwrap().acting == Some(state.position) {
+            let reply = format!("{}:c\r\n", line.trim_end());
+            stream.get_mut().write_all(reply.as_bytes()).unwrap();
+          }
+          states.push(state);
+          line.clear();
+        }
+        states
+      })
+    })
+    .collect();
+
+  let mut players = PlayersAcpc::tcp_accept(&listener, action_class, 2).unwrap();
+  let ref mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let funds = vec![(0, Money::new(200, 0)), (1, Money::new(200, 0))];
+  let (log, _) = table_game_simulate(rng, sim, eval, &mut players, &funds, 0).unwrap();
+  assert!(players.error.is_none());
+
+  drop(players);
+  let states: Vec<Vec<MatchState>> = clients.into_iter().map(|c| c.join().unwrap()).collect();
+
+  // A state by decision, both seats acting in every round, then the final state.
+  for xs in states.iter() {
+    assert!(xs.len() == 9);
+    let bettings: Vec<_> = xs.iter().map(|x| x.betting.clone()).collect();
+    let bettings_other: Vec<_> = states[0].iter().map(|x| x.betting.clone()).collect();
+    assert!(bettings == bettings_other);
+
+    let last = xs.last().unwrap();
+    assert!(last.to_string().contains(":cc/cc/cc/cc:"));
+    assert!(last.board_cards.len() == 3);
+    assert!(last.hole_cards.iter().all(|cards| cards.len() == 2));
+    assert!(xs[..8].iter().all(|x| x.hole_cards.iter().filter(|cs| cs.is_empty()).count() == 1));
+  }
+
+  // Hands shown at showdown, by position.
+  let &(_, _, ref cards) = log.players_init.iter().find(|&&(s, _, _)| s == 0).unwrap();
+  assert!(states[1].last().unwrap().hole_cards[0] == *cards);
+}
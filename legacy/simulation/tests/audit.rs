@@ -0,0 +1,14 @@
+extern crate anna_simulation;
+
+#[test]
+fn fuzz_games() {
+  use anna_simulation::audit::fuzz_game;
+
+  for seed in 0..500 {
+    let violations = fuzz_game(seed);
+    assert!(violations.is_empty(), "seed {}: {:?}", seed, violations);
+  }
+
+  // Games are replayed given their seed.
+  assert!(fuzz_game(42) == fuzz_game(42));
+}
@@ -0,0 +1,184 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_cfr() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    cfr::{Cfr, PlayersStrategy},
+    exploit::exploitability_tree,
+    perf::run_benchmark,
+    players::PlayersFold,
+    players_mul::PlayersMul2,
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let mut cfr = Cfr::new(sim, eval, &vec![fund; 2], false).unwrap();
+  cfr.run(1000);
+
+  let e = exploitability_tree(&cfr.tree, &cfr.strategy(), blind);
+  assert!(e.mbb < 10.);
+  // Game value of the first player is -1/18 of the ante.
+  assert!((e.values[0] + 100. / 18.).abs() < 1.);
+
+  // Playing from both positions against a player always folding.
+  let rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let ref mut players = PlayersMul2(
+    (PlayersStrategy::new(rng, action_kuhn, cfr.strategy()), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  );
+
+  let funds = vec![(0, fund), (1, fund)];
+  let rates = run_benchmark(sim, eval, &funds, 1000, players);
+  assert!(rates[0] > 0.);
+}
+
+#[test]
+fn kuhn_cfr_plus_reference() {
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{cfr::Cfr, exploit::exploitability_tree, OddChip, Sim};
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  // Exploitability of the uniform average of alternating CFR+, regrets being floored once per
+  // iteration (regrets floored along the traversal give 84.18 and 23.23 mbb).
+  let mut cfr = Cfr::new(sim, eval, &vec![Money::new(100, 0); 2], false).unwrap();
+
+  cfr.run(10);
+  let e = exploitability_tree(&cfr.tree, &cfr.strategy(), blind);
+  assert!((e.mbb - 58.541).abs() < 0.05);
+
+  cfr.run(90);
+  let e = exploitability_tree(&cfr.tree, &cfr.strategy(), blind);
+  assert!((e.mbb - 4.347).abs() < 0.05);
+  assert!((e.values[0] + 5.495).abs() < 0.01);
+}
+
+#[test]
+fn kuhn_strategy_history() {
+  use rand::{SeedableRng, StdRng};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Action, Money};
+  use anna_simulation::{
+    cfr::PlayersStrategy, engine::table_game_simulate, exploit::Strategy, tree::Tree, OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: Money::new(1, 0),
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let fund = Money::new(100, 0);
+  let tree = Tree::build(sim, eval, &vec![fund; 2], 0).unwrap();
+
+  // Check, bet and call, folding only on unknown information sets.
+  let strategy: Strategy = tree
+    .infosets()
+    .keys()
+    .map(|key| {
+      let probs = if key.ends_with("|1") { vec![0., 0., 1.] } else { vec![0., 1., 0.] };
+      (key.clone(), probs)
+    })
+    .collect();
+
+  let ref mut players = PlayersStrategy::new(rng.clone(), action_kuhn, strategy);
+
+  for _ in 0..50 {
+    let (log, _) =
+      table_game_simulate(&mut rng, sim, eval, players, &vec![(0, fund), (1, fund)], 0).unwrap();
+    let actions: Vec<Action> =
+      log.rounds.iter().flat_map(|xs| xs.iter().map(|&(_, a, _)| a)).collect();
+    assert!(!actions.contains(&Action::Fold));
+  }
+}
+
+#[test]
+fn leduc_cfr_linear() {
+  use std::env;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{
+    cfr::{strategy_load, strategy_save, Cfr},
+    exploit::exploitability_tree,
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::naive();
+
+  let ref profile = profile_leduc(2);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let blind = Money::new(1, 0);
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let mut cfr = Cfr::new(sim, eval, &vec![Money::new(100, 0); 2], true).unwrap();
+
+  cfr.run(10);
+  let e_start = exploitability_tree(&cfr.tree, &cfr.strategy(), blind);
+  cfr.run(90);
+  let e_end = exploitability_tree(&cfr.tree, &cfr.strategy(), blind);
+  assert!(e_end.mbb < e_start.mbb);
+  assert!(e_end.mbb < 200.);
+
+  let path = env::temp_dir().join("anna_leduc_cfr.bin");
+  strategy_save(&path, &cfr.strategy()).unwrap();
+  assert!(strategy_load(&path).unwrap() == cfr.strategy());
+}
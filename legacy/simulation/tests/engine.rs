@@ -11,7 +11,7 @@ fn kuhn_sample_game() {
 
   use anna_eval::Eval;
   use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
-  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, Sim};
+  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, OddChip, Sim};
 
   let seed: &[_] = &[0];
 
@@ -30,7 +30,14 @@ fn kuhn_sample_game() {
 
   let ref action_kuhn = ActionKuhn {};
 
-  let ref sim = Sim { action_class: action_kuhn, blind_biggest: blind, profile: profile_kuhn(3) };
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(3),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
 
   let ref mut players = PlayersRand { action_class: action_kuhn, blind_biggest: blind, rng: rng };
 
@@ -54,7 +61,7 @@ fn leduc_sample_game() {
 
   use anna_eval::Eval;
   use anna_model::{classifiers::ActionLimit, profile::profile_leduc, Money};
-  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, Sim};
+  use anna_simulation::{engine::table_game_simulate, players::PlayersRand, OddChip, Sim};
 
   let seed: &[_] = &[0];
 
@@ -72,7 +79,14 @@ fn leduc_sample_game() {
 
   let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
 
-  let ref sim = Sim { action_class: action_leduc, blind_biggest: blind, profile: profile.clone() };
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
 
   let ref mut players = PlayersRand { action_class: action_leduc, blind_biggest: blind, rng: rng };
 
@@ -89,3 +103,599 @@ fn leduc_sample_game() {
 
   assert!(true == true);
 }
+
+#[test]
+fn side_pots_split() {
+  use anna_model::{cards::CARDS, classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{engine::funds_update, OddChip, Sim, State};
+
+  let blind = Money::new(1, 0);
+
+  let ref profile = profile_leduc(4);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let mut sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let play = |i: usize, all_in| State::Play { cards: vec![CARDS[i]], all_in: all_in };
+
+  // Seat 0 is all-in short, seat 1 folded, seats 2 and 3 split the side pot.
+  let player_states = vec![play(0, true), State::Folded, play(1, false), play(2, false)];
+  let player_pots = vec![blind * 2, blind, blind * 5, blind * 5];
+  let finalists = vec![(0, 0.9), (2, 0.5), (3, 0.5)];
+
+  let mut player_funds = vec![Money::zero(); 4];
+  let pots = funds_update(&sim, &mut player_funds, &player_pots, &player_states, &finalists, 3);
+
+  assert!(pots.len() == 2);
+  assert!(pots[0].amount == blind * 7);
+  assert!(pots[0].seats == vec![0, 2, 3]);
+  assert!(pots[0].winners == vec![0]);
+  assert!(pots[1].amount == blind * 6);
+  assert!(pots[1].seats == vec![2, 3]);
+  assert!(player_funds == vec![blind * 7, Money::zero(), blind * 3, blind * 3]);
+
+  // Odd chips go first to the left of the button, or to the lowest card.
+  let player_pots = vec![Money::zero(), Money::from_u32(1), blind, blind];
+  let finalists = vec![(2, 0.5), (3, 0.5)];
+
+  let mut player_funds = vec![Money::zero(); 4];
+  funds_update(&sim, &mut player_funds, &player_pots, &player_states, &finalists, 2);
+  assert!(player_funds[3] == Money::from_u32(101));
+
+  sim.odd_chip = OddChip::SuitLowest;
+
+  let mut player_funds = vec![Money::zero(); 4];
+  funds_update(&sim, &mut player_funds, &player_pots, &player_states, &finalists, 2);
+  assert!(player_funds[2] == Money::from_u32(101));
+}
+
+#[test]
+fn kuhn_game_end() {
+  use rand::{SeedableRng, StdRng};
+
+  use anna_eval::Eval;
+  use anna_model::{cards::Card, classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    engine::{table_game_simulate, Score},
+    players::{Players, PlayersRand},
+    Event, OddChip, SeatId, Sim,
+  };
+
+  // Random players keeping the notified game ends.
+  struct PlayersEnd<P> {
+    players: P,
+    ends: Vec<(Score, Vec<(SeatId, Vec<Card>)>)>,
+  }
+
+  impl<P: Players<()>> Players<()> for PlayersEnd<P> {
+    fn init(&mut self, b: &[Money], p: SeatId, f: &[Money], h: &Vec<(usize, Vec<Card>)>) -> () {
+      self.players.init(b, p, f, h)
+    }
+
+    fn play(
+      &mut self,
+      round_id: usize,
+      table_target: Money,
+      table_target_raise: Option<Money>,
+      player_pots: &[Money],
+      seat_id: usize,
+      player_fund: Money,
+      events: &[Event],
+    ) -> Result<u8, ()> {
+      self.players.play(
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        events,
+      )
+    }
+
+    fn game_end(&mut self, score: &Score, showdown: &[(SeatId, Vec<Card>)]) -> () {
+      self.ends.push((score.clone(), showdown.to_vec()));
+    }
+  }
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let blind = Money::new(1, 0);
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let rng_players: StdRng = SeedableRng::from_seed([1; 32]);
+
+  let ref mut players =
+    PlayersEnd { players: PlayersRand::new(rng_players, action_kuhn, blind), ends: Vec::new() };
+
+  let mut showdowns = 0;
+
+  for i in 0..20 {
+    let (log, score) =
+      table_game_simulate(&mut rng, sim, eval, players, &funds, i % 2).map_err(|(_, e)| e).unwrap();
+
+    let (ref score_end, ref showdown) = players.ends[i];
+    assert!(players.ends.len() == i + 1);
+    assert!(score_end.player_funds == score.player_funds && score_end.pots == score.pots);
+
+    // Shown hands are the dealt ones.
+    for &(seat_id, ref cards) in showdown.iter() {
+      assert!(log.players_init.iter().any(|&(s, _, ref cs)| s == seat_id && cs == cards));
+    }
+    showdowns += !showdown.is_empty() as usize;
+
+    // The log closes with the blinds (ante), the showdown and the awards of the whole pot.
+    let mut blinds = Money::zero();
+    let mut awards = Money::zero();
+    let mut shown = false;
+    for event in log.events.iter() {
+      match event {
+        &Event::Blind { amount, .. } => blinds = blinds + amount,
+        &Event::Showdown { ref hands } => shown = hands.len() == showdown.len(),
+        &Event::Award { amount, .. } => awards = awards + amount,
+        _ => {}
+      }
+    }
+    assert!(blinds == blind * 2);
+    assert!(shown == !showdown.is_empty());
+    assert!(awards == score.pot);
+  }
+
+  assert!(showdowns > 0 && showdowns < 20);
+}
+
+#[test]
+fn policy_play() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{
+    cards::Card,
+    classifiers::{ActionKuhn, ActionLimit},
+    profile::{profile_kuhn, profile_texas_limit},
+    ActionClass, Money,
+  };
+  use anna_simulation::{
+    engine::table_game_simulate,
+    players::{Players, PlayersRand},
+    players_kuhn::Kuhn2,
+    players_lex::Lex,
+    players_mul::PlayersMul2,
+    Event, OddChip, SeatId, Sim,
+  };
+
+  // Players asked for their policy before every play, the played action having to be in it.
+  struct PlayersPolicy<'a, P> {
+    players: P,
+    action_class: &'a ActionClass,
+    blind_biggest: Money,
+    decisions: usize,
+  }
+
+  impl<'a, P: Players<()>> Players<()> for PlayersPolicy<'a, P> {
+    fn init(&mut self, b: &[Money], p: SeatId, f: &[Money], h: &Vec<(usize, Vec<Card>)>) -> () {
+      self.players.init(b, p, f, h)
+    }
+
+    fn play(
+      &mut self,
+      round_id: usize,
+      table_target: Money,
+      table_target_raise: Option<Money>,
+      player_pots: &[Money],
+      seat_id: usize,
+      player_fund: Money,
+      events: &[Event],
+    ) -> Result<u8, ()> {
+      let policy = self.players.policy(
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        events,
+      );
+      let mask = self.action_class.normalize(
+        self.blind_biggest,
+        round_id,
+        table_target,
+        table_target_raise,
+        player_fund,
+        player_pots[seat_id],
+      );
+
+      assert!(policy.len() == self.action_class.size());
+      assert!((policy.iter().sum::<f32>() - 1.).abs() < 1e-4);
+      for (i, &p) in policy.iter().enumerate() {
+        assert!(p >= 0. && (p == 0. || mask.contains(&i)));
+      }
+
+      let action = self.players.play(
+        round_id,
+        table_target,
+        table_target_raise,
+        player_pots,
+        seat_id,
+        player_fund,
+        events,
+      )?;
+      assert!(policy[action as usize] > 0.);
+
+      self.decisions += 1;
+      Ok(action)
+    }
+  }
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let rng_players: StdRng = SeedableRng::from_seed([1; 32]);
+
+  let blind = Money::new(1, 0);
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  // Kuhn
+  let ref eval = Eval::naive();
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let ref mut players = PlayersPolicy {
+    players: PlayersMul2(
+      (Kuhn2::new(rng_players.clone(), action_kuhn), HashSet::from_iter(vec![0])),
+      (PlayersRand::new(rng_players.clone(), action_kuhn, blind), HashSet::from_iter(vec![1])),
+    ),
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    decisions: 0,
+  };
+
+  for i in 0..100 {
+    table_game_simulate(&mut rng, sim, eval, players, &funds, i % 2).map_err(|(_, e)| e).unwrap();
+  }
+  assert!(players.decisions > 100);
+
+  // Texas limit
+  let ref eval = Eval::texas();
+  let ref profile = profile_texas_limit(2, blind / 2, blind);
+  let ref action_limit = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_limit,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  // A `Lex` per seat, its table cards being kept from the events.
+  let lex = || {
+    PlayersMul2(
+      (Lex::new(rng_players.clone(), 16, action_limit, eval, profile), HashSet::from_iter(vec![0])),
+      (Lex::new(rng_players.clone(), 16, action_limit, eval, profile), HashSet::from_iter(vec![1])),
+    )
+  };
+  let ref mut players = PlayersPolicy {
+    players: lex(),
+    action_class: action_limit,
+    blind_biggest: blind,
+    decisions: 0,
+  };
+  let ref mut players_alone = lex();
+
+  // Asking the policy leaves the games played unchanged.
+  for i in 0..20 {
+    let mut rng_alone = rng.clone();
+    let (log, _) =
+      table_game_simulate(&mut rng, sim, eval, players, &funds, i % 2).map_err(|(_, e)| e).unwrap();
+    let (log_alone, _) =
+      table_game_simulate(&mut rng_alone, sim, eval, players_alone, &funds, i % 2)
+        .map_err(|(_, e)| e)
+        .unwrap();
+    assert!(format!("{:?}", log.events) == format!("{:?}", log_alone.events));
+  }
+  assert!(players.decisions > 20);
+}
+
+#[test]
+fn kuhn_timeout() {
+  use rand::{SeedableRng, StdRng};
+  use std::thread::sleep;
+  use std::time::{Duration, Instant};
+
+  use anna_eval::Eval;
+  use anna_model::{cards::Card, classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    engine::table_game_simulate, players::Players, Act, Budget, Event, OddChip, SeatId, Sim,
+  };
+
+  // The first seat plays `action`, the second one calls after `delay`.
+  struct PlayersSlow {
+    action: u8,
+    delay: Duration,
+    plays: usize,
+    deadline: Option<Instant>,
+  }
+
+  impl Players<()> for PlayersSlow {
+    fn init(&mut self, _: &[Money], _: SeatId, _: &[Money], _: &Vec<(usize, Vec<Card>)>) -> () {}
+
+    fn play(
+      &mut self,
+      _: usize,
+      _: Money,
+      _: Option<Money>,
+      _: &[Money],
+      seat_id: usize,
+      _: Money,
+      events: &[Event],
+    ) -> Result<u8, ()> {
+      // Timeouts are kept to the log, the deadline of the decision being given.
+      assert!(events.iter().all(|e| match e {
+        &Event::Timeout { .. } => false,
+        _ => true,
+      }));
+      assert!(self.deadline.take().map_or(false, |deadline| deadline > Instant::now()));
+
+      self.plays += 1;
+      if seat_id == 0 {
+        Ok(self.action)
+      } else {
+        sleep(self.delay);
+        Ok(1)
+      }
+    }
+
+    fn deadline(&mut self, _: SeatId, deadline: Option<Instant>) -> () {
+      self.deadline = deadline;
+    }
+  }
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let blind = Money::new(1, 0);
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let mut sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: false,
+    odd_chip: OddChip::ButtonLeft,
+    budget: Some(Budget { decision: Duration::from_millis(5), game: Duration::from_secs(10) }),
+  };
+
+  let slow = |action, delay| PlayersSlow {
+    action: action,
+    delay: Duration::from_millis(delay),
+    plays: 0,
+    deadline: None,
+  };
+
+  let timeouts = |events: &[Event]| -> Vec<(SeatId, u8)> {
+    events
+      .windows(2)
+      .filter_map(|w| match (&w[0], &w[1]) {
+        (&Event::Timeout { seat_id }, &Event::Play(Act { seat_id: s, action })) => {
+          assert!(seat_id == s);
+          Some((seat_id, action))
+        }
+        _ => None,
+      })
+      .collect()
+  };
+
+  // Facing a bet the slow seat folds, otherwise it checks.
+  let ref mut players = slow(2, 20);
+  let (log, score) =
+    table_game_simulate(&mut rng, &sim, eval, players, &funds, 0).map_err(|(_, e)| e).unwrap();
+  assert!(timeouts(&log.events) == vec![(1, 0)]);
+  assert!(score.winners == vec![0]);
+
+  let ref mut players = slow(1, 20);
+  let (log, _) =
+    table_game_simulate(&mut rng, &sim, eval, players, &funds, 0).map_err(|(_, e)| e).unwrap();
+  assert!(timeouts(&log.events) == vec![(1, 1)]);
+
+  // Within budget, the actions are kept.
+  let ref mut players = slow(2, 0);
+  let (log, _) =
+    table_game_simulate(&mut rng, &sim, eval, players, &funds, 0).map_err(|(_, e)| e).unwrap();
+  assert!(timeouts(&log.events).is_empty());
+
+  // Out of game budget, the seat isn't asked anymore.
+  sim.budget = Some(Budget { decision: Duration::from_secs(10), game: Duration::from_millis(5) });
+  let ref mut players = slow(2, 20);
+  let (log, _) =
+    table_game_simulate(&mut rng, &sim, eval, players, &funds, 1).map_err(|(_, e)| e).unwrap();
+  assert!(timeouts(&log.events) == vec![(1, 1), (1, 0)]);
+  assert!(players.plays == 2);
+
+  // Strict mode plays the default action too, the game going on.
+  sim.strict = true;
+  sim.budget = Some(Budget { decision: Duration::from_millis(5), game: Duration::from_secs(10) });
+  let ref mut players = slow(2, 20);
+  let (log, score) =
+    table_game_simulate(&mut rng, &sim, eval, players, &funds, 0).map_err(|(_, e)| e).unwrap();
+  assert!(timeouts(&log.events) == vec![(1, 0)]);
+  assert!(score.winners == vec![0]);
+}
+
+#[test]
+fn play_errors() {
+  use rand::{SeedableRng, StdRng};
+
+  use anna_eval::Eval;
+  use anna_model::{
+    cards::Card,
+    classifiers::{ActionKuhn, ActionLimit},
+    profile::{profile_kuhn, profile_leduc},
+    ActionClass, Money,
+  };
+  use anna_simulation::{
+    engine::{table_game_simulate, Error},
+    players::Players,
+    Event, OddChip, SeatId, Sim,
+  };
+
+  // Players always playing the same action, legal or not.
+  struct PlayersConst(u8);
+
+  impl Players<()> for PlayersConst {
+    fn init(&mut self, _: &[Money], _: SeatId, _: &[Money], _: &Vec<(usize, Vec<Card>)>) -> () {}
+
+    fn play(
+      &mut self,
+      _: usize,
+      _: Money,
+      _: Option<Money>,
+      _: &[Money],
+      _: usize,
+      _: Money,
+      _: &[Event],
+    ) -> Result<u8, ()> {
+      Ok(self.0)
+    }
+  }
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let blind = Money::new(1, 0);
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  // Unknown action classes are rejected by the classifiers and the engine.
+  let ref action_kuhn = ActionKuhn {};
+  assert!(action_kuhn.unapply(blind, 0, blind, blind, blind, blind, 7).is_err());
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let result = table_game_simulate(&mut rng, sim, eval, &mut PlayersConst(7), &funds, 0);
+  assert!(result.map_err(|(_, e)| e).err() == Some(Error::InvalidAction { seat_id: 0, action: 7 }));
+
+  // Raising above the cap of a limit game fails the game.
+  let profile = profile_leduc(2);
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: blind,
+    profile: profile,
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let result = table_game_simulate(&mut rng, sim, eval, &mut PlayersConst(2), &funds, 0);
+  match result.map_err(|(_, e)| e) {
+    Err(Error::Sementic(_)) => {}
+    _ => panic!("Raise above the cap not rejected."),
+  }
+}
+
+#[test]
+fn table_next_errors() {
+  use anna_model::{cards::CARDS, classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    engine::{table_next, Error},
+    rounds::pledge_unapply,
+    OddChip, Sim, State,
+  };
+
+  let blind = Money::new(1, 0);
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let play = |i: usize| State::Play { cards: vec![CARDS[i]], all_in: false };
+
+  // The seat which set the target is back to act below it.
+  let player_states = vec![play(0), play(1)];
+  let player_pots = vec![blind * 2, blind];
+  let mut table_target_raise = blind;
+  let mut table_target_by = Some(1);
+  let next = table_next::<()>(
+    sim,
+    &player_pots,
+    &player_states,
+    &(blind * 2),
+    &mut table_target_raise,
+    &mut table_target_by,
+    0,
+    0,
+    0,
+  );
+  assert!(next == Err(Error::PotOffTarget { seat_id: 1, pot: blind, target: blind * 2 }));
+
+  // No active seat around the table for the one left behind the target.
+  let player_states = vec![State::Folded, State::Folded, play(2)];
+  let player_pots = vec![blind, blind, blind];
+  let next = table_next::<()>(
+    sim,
+    &player_pots,
+    &player_states,
+    &(blind * 2),
+    &mut table_target_raise,
+    &mut table_target_by,
+    0,
+    0,
+    0,
+  );
+  assert!(next == Err(Error::NoActiveSeat { seat_id: 0 }));
+
+  // Taking back more than pledged.
+  let mut player_funds = vec![Money::zero(); 2];
+  let mut player_pots = vec![blind, blind];
+  let unapply = pledge_unapply::<()>(&mut player_funds, &mut player_pots, 1, blind * 2);
+  assert!(unapply == Err(Error::InsufficientPot { seat_id: 1, pot: blind, unpledge: blind * 2 }));
+  assert!(player_funds == vec![Money::zero(); 2]);
+}
@@ -0,0 +1,71 @@
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+extern crate rand;
+
+#[test]
+fn kuhn_exploitability() {
+  use anna_eval::Eval;
+  use anna_model::{cards::KUHN_CARDS, classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    exploit::{exploitability, players_policy},
+    game::GameState,
+    players::PlayersRand,
+    OddChip, Sim,
+  };
+  use rand::thread_rng;
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: Money::new(1, 0),
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let funds = vec![Money::new(100, 0); 2];
+
+  let uniform = |state: &GameState| vec![1.; state.sim.action_class.size()];
+
+  // Equilibrium of the first player never betting first with a Jack.
+  let nash = |state: &GameState| {
+    let seat_id = state.current_player().unwrap();
+    let card = state.players_init[seat_id].2[0];
+    let mut deck = KUHN_CARDS.to_vec();
+    deck.sort();
+    let rank = deck.iter().position(|&c| c == card).unwrap();
+
+    let (fold, call, raise) = match (seat_id, state.actions().as_slice(), rank) {
+      (0, &[], _) => (0., 1., 0.),
+      (0, _, 0) => (1., 0., 0.),
+      (0, _, 1) => (2. / 3., 1. / 3., 0.),
+      (0, _, _) => (0., 1., 0.),
+      (1, &[1], 0) => (0., 2. / 3., 1. / 3.),
+      (1, &[1], 1) => (0., 1., 0.),
+      (1, &[1], _) => (0., 0., 1.),
+      (1, _, 0) => (1., 0., 0.),
+      (1, _, 1) => (2. / 3., 1. / 3., 0.),
+      (_, _, _) => (0., 1., 0.),
+    };
+    vec![fold, call, raise]
+  };
+
+  let e = exploitability(sim, eval, &funds, 0, nash).unwrap();
+  assert!(e.mbb.abs() < 1.);
+  // Game value of the first player is -1/18 of the ante.
+  assert!((e.values[0] + 100. / 18.).abs() < 0.1);
+
+  let e = exploitability(sim, eval, &funds, 0, uniform).unwrap();
+  assert!((e.mbb - 458.33).abs() < 1.);
+  assert!(e.best_responses.iter().zip(e.values.iter()).all(|(br, v)| br >= v));
+
+  // Same strategy, as played by a player.
+  let ref mut players = PlayersRand::new(thread_rng(), action_kuhn, Money::new(1, 0));
+  let e = exploitability(sim, eval, &funds, 0, |state| players_policy(players, state)).unwrap();
+  assert!((e.mbb - 458.33).abs() < 1.);
+}
@@ -0,0 +1,107 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn leduc_game_state() {
+  use rand::{Rng, SeedableRng, StdRng};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{game::GameState, OddChip, Sim};
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+
+  let funds = vec![(0, fund), (1, fund), (2, fund)];
+
+  let ref profile = profile_leduc(3);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: blind,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  for _ in 0..100 {
+    let mut states = vec![GameState::deal(&mut rng, sim, eval, &funds, 0).unwrap()];
+
+    while !states.last().unwrap().is_terminal() {
+      let state = states.last().unwrap().clone();
+      assert!(state.payoffs().is_none());
+
+      let actions = state.legal_actions();
+      let action = *rng.choose(&actions).unwrap();
+      states.push(state.apply(action).unwrap());
+    }
+
+    let state = states.last().unwrap();
+    assert!(state.current_player().is_none());
+    assert!(state.legal_actions().is_empty());
+    assert!(state.payoffs().unwrap().iter().map(|&(_, m)| m).sum::<i32>() == 0);
+
+    // Undo every action back to the deal.
+    let mut state = state.clone();
+    for previous in states.iter().rev().skip(1) {
+      state = state.undo().unwrap();
+      assert!(state.actions() == previous.actions());
+      assert!(state.player_pots == previous.player_pots);
+      assert!(state.current_player() == previous.current_player());
+    }
+    assert!(state.undo().is_none());
+  }
+}
+
+#[test]
+fn leduc_all_in_runout() {
+  use anna_eval::Eval;
+  use anna_model::{cards::LEDUC_CARDS, classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{game::GameState, OddChip, Sim};
+
+  let ref eval = Eval::naive();
+
+  let ref profile = profile_leduc(2);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: Money::new(1, 0),
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  // Raising in round 0 puts both players all-in.
+  let fund = Money::new(3, 0);
+  let players_init = vec![(0, fund, vec![LEDUC_CARDS[4]]), (1, fund, vec![LEDUC_CARDS[0]])];
+
+  let state = GameState::new(sim, eval, &players_init, &Vec::new(), 0).unwrap();
+  let state = state.apply(2).unwrap().apply(1).unwrap();
+
+  // The table card is dealt before the showdown.
+  assert!(!state.is_terminal());
+  assert!(state.is_chance());
+  assert!(state.current_player().is_none());
+  assert!(state.payoffs().is_none());
+  assert!(state.chance_outcomes().len() == 4);
+
+  let state = state.apply_chance(&[LEDUC_CARDS[1]]).unwrap();
+  assert!(state.is_terminal());
+  assert!(state.payoffs().unwrap() == vec![(0, -300), (1, 300)]);
+
+  let state = state.undo().unwrap();
+  assert!(state.is_chance());
+}
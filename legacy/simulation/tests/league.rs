@@ -0,0 +1,74 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_league() {
+  use rand::{SeedableRng, StdRng};
+  use std::env;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    league::{league_run, schedule, Ratings, Schedule},
+    players::{Players, PlayersFold, PlayersRand},
+    players_kuhn::Kuhn2,
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let players_rng = rng.clone();
+  let players = |name: &str| -> Box<Players<()>> {
+    match name {
+      "fold" => Box::new(PlayersFold::new(action_kuhn)),
+      "rand" => Box::new(PlayersRand::new(players_rng.clone(), action_kuhn, blind)),
+      "kuhn2" => Box::new(Kuhn2::new(players_rng.clone(), action_kuhn)),
+      _ => unreachable!(),
+    }
+  };
+
+  let mut ratings = Ratings::new(32.);
+  let names = vec!["fold".to_string(), "rand".to_string()];
+  let matches = schedule(&mut rng, &names, Schedule::RoundRobin);
+  assert!(matches.len() == 1);
+
+  let results = league_run(&mut rng, sim, eval, fund, &mut ratings, &matches, 1000, players);
+  assert!(results[0].2.mean < 0.);
+  assert!(ratings.elo("rand") > 1500. && ratings.elo("fold") < 1500.);
+
+  // Ratings are kept across runs, new entries slotted in.
+  let ref path = env::temp_dir().join("kuhn_league.json");
+  ratings.save(path).unwrap();
+  let mut ratings = Ratings::load(path).unwrap();
+  assert!(ratings.entries["rand"].matches == 1 && ratings.entries["rand"].hands == 1000);
+
+  let names = vec!["fold".to_string(), "rand".to_string(), "kuhn2".to_string()];
+  let matches = schedule(&mut rng, &names, Schedule::Sampled(12));
+  assert!(matches.len() == 12 && matches.iter().all(|&(ref a, ref b)| a != b));
+
+  let matches = schedule(&mut rng, &names, Schedule::RoundRobin);
+  league_run(&mut rng, sim, eval, fund, &mut ratings, &matches, 1000, players);
+
+  let table = ratings.table();
+  assert!(table.len() == 3);
+  assert!(table[2].0 == "fold");
+}
@@ -0,0 +1,126 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_mccfr() {
+  use rand::{SeedableRng, StdRng};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    exploit::exploitability_tree,
+    mccfr::{Mccfr, Sampling},
+    tree::Tree,
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let funds = vec![Money::new(100, 0); 2];
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let tree = Tree::build(sim, eval, &funds, 0).unwrap();
+
+  for &(sampling, mbb) in [(Sampling::External, 20.), (Sampling::Outcome, 50.)].iter() {
+    let mut mccfr = Mccfr::new(sim, eval, None, &funds, sampling);
+    mccfr.run(&mut rng, 20_000).unwrap();
+
+    let e = exploitability_tree(&tree, &mccfr.strategy(), blind);
+    assert!(e.mbb < mbb);
+  }
+}
+
+#[test]
+fn leduc_mccfr_abstraction() {
+  use rand::{SeedableRng, StdRng};
+  use std::env;
+
+  use anna_eval::Eval;
+  use anna_model::{cards::Card, classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{
+    cfr::PlayersStrategy,
+    exploit::{exploitability, players_policy, Strategy},
+    mccfr::{Mccfr, Sampling},
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref profile = profile_leduc(2);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: Money::new(1, 0),
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  // Paired with the table or not, the suit being ignored.
+  let pairs = |hand: &[Card], board: &[Card]| {
+    let paired = board.iter().any(|c| c.value == hand[0].value);
+    hand[0].value as usize * 2 + paired as usize
+  };
+
+  let funds = vec![Money::new(100, 0); 2];
+
+  // Exploitability in the full game of the abstracted strategy, as played.
+  let exploitability_mbb = |strategy: Strategy| {
+    let rng: StdRng = SeedableRng::from_seed([0; 32]);
+    let ref mut players = PlayersStrategy::with_abstraction(rng, action_leduc, strategy, &pairs);
+    exploitability(sim, eval, &funds, 0, |state| players_policy(players, state)).unwrap().mbb
+  };
+
+  let uniform = exploitability_mbb(Strategy::new());
+
+  for &(sampling, mbb) in [(Sampling::External, 900.), (Sampling::Outcome, 1400.)].iter() {
+    let mut mccfr = Mccfr::new(sim, eval, Some(&pairs), &funds, sampling);
+    mccfr.run(&mut rng, 2000).unwrap();
+
+    let strategy = mccfr.strategy();
+    assert!(strategy.keys().all(|key| key.contains("|#")));
+
+    let e = exploitability_mbb(strategy);
+    assert!(e < mbb && e < uniform);
+  }
+
+  let mut mccfr = Mccfr::new(sim, eval, Some(&pairs), &funds, Sampling::External);
+  mccfr.run(&mut rng, 500).unwrap();
+
+  // Checkpoint and resume, the tables being the same along the next iterations.
+  let path = env::temp_dir().join("anna_leduc_mccfr.bin");
+  mccfr.checkpoint(&path).unwrap();
+
+  let mut mccfr_resumed = Mccfr::new(sim, eval, Some(&pairs), &funds, Sampling::External);
+  mccfr_resumed.resume(&path).unwrap();
+  assert!(mccfr_resumed.tables.iterations == 500);
+  assert!(mccfr_resumed.tables.regrets == mccfr.tables.regrets);
+  assert!(mccfr_resumed.tables.strategy_sum == mccfr.tables.strategy_sum);
+
+  mccfr.run(&mut rng.clone(), 10).unwrap();
+  mccfr_resumed.run(&mut rng, 10).unwrap();
+  assert!(mccfr_resumed.tables.iterations == 510);
+  assert!(mccfr_resumed.tables.regrets == mccfr.tables.regrets);
+  assert!(mccfr_resumed.tables.strategy_sum == mccfr.tables.strategy_sum);
+}
@@ -0,0 +1,112 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn opponent_stats_events() {
+  use anna_model::{classifiers::ActionLimit, Money};
+  use anna_simulation::{
+    engine::{Pot, Score},
+    opponent_stats::OpponentStats,
+    Act, Event,
+  };
+
+  let ref action_class = ActionLimit { raises: vec![3, 4, 4, 4] };
+  let play = |seat_id, action| Event::Play(Act { seat_id: seat_id, action: action });
+  let table = || Event::Table { cards: Vec::new() };
+  let blind = |seat_id, amount| Event::Blind { seat_id: seat_id, amount: amount };
+
+  let (blind_small, blind_big) = (Money::new(0, 50), Money::new(1, 0));
+  let funds = vec![Money::new(100, 0); 2];
+
+  let mut stats = OpponentStats::new(action_class);
+
+  // Raise, 3-bet and call preflop, bet and call on the flop, fold to a bet on the turn.
+  stats.game_start(&funds);
+  stats.observe(&[blind(0, blind_small), blind(1, blind_big), play(0, 2), play(1, 2), play(0, 1)]);
+  stats.observe(&[table(), play(1, 1), play(0, 2), play(1, 1), table(), play(1, 2), play(0, 0)]);
+  stats.game_end(&Score {
+    player_funds: vec![(0, Money::new(94, 0)), (1, Money::new(106, 0))],
+    pot: Money::new(12, 0),
+    pots: vec![Pot {
+      amount: Money::new(12, 0),
+      seats: vec![1],
+      winners: vec![1],
+      awards: vec![(1, Money::new(12, 0))],
+    }],
+    winners: vec![1],
+    winners_score: 0.,
+  });
+
+  // Limp and check, the end of the game is unknown.
+  stats.game_start(&funds);
+  stats.observe(&[blind(1, blind_small), blind(0, blind_big), play(1, 1), play(0, 1)]);
+  stats.game_start(&funds);
+
+  let (s0, s1) = (stats.seat(0), stats.seat(1));
+  assert!(s0.hands == 2 && s1.hands == 2);
+  assert!(s0.vpip == 1 && s0.pfr == 1 && s0.three_bet_chances == 0);
+  assert!(s1.vpip == 2 && s1.pfr == 1 && s1.three_bet == 1 && s1.three_bet_chances == 1);
+  assert!(s0.bets == 1 && s0.calls == 0 && s1.bets == 1 && s1.calls == 1);
+  assert!(s0.flops == 1 && s0.showdowns == 0 && s0.wtsd() == Some(0.));
+  assert!(s1.af() == Some(1.) && s0.af().is_none());
+}
+
+#[test]
+fn opponent_stats_players() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_texas_limit, Money};
+  use anna_simulation::{
+    opponent_stats::PlayersStats,
+    perf::run_benchmark,
+    players::PlayersFold,
+    players_mul::PlayersMul2,
+    players_rules::{PlayersRules, Style},
+    OddChip, Sim,
+  };
+
+  let rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::texas();
+
+  let blind_big = Money::new(1, 0);
+  let ref profile = profile_texas_limit(2, blind_big / 2, blind_big);
+
+  let ref action_class = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind_big,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  let ref mut players = PlayersStats::new(
+    PlayersMul2(
+      (
+        PlayersRules::new(rng, 16, action_class, eval, profile, Style::maniac()),
+        HashSet::from_iter(vec![0]),
+      ),
+      (PlayersFold::new(action_class), HashSet::from_iter(vec![1])),
+    ),
+    action_class,
+  );
+
+  run_benchmark(sim, eval, &funds, 200, players);
+
+  // Every game end is notified by the engine.
+  let (s0, s1) = (players.stats.seat(0), players.stats.seat(1));
+  assert!(s0.hands == 200 && s1.hands == 200);
+  assert!(s1.vpip == 0 && s1.pfr == 0);
+  // Playing nearly every hand from the small blind, the big blind being given up to it.
+  assert!(s0.vpip > 90 && s0.pfr > 0 && s0.calls == 0);
+}
@@ -10,6 +10,264 @@ extern crate anna_simulation;
 
 use test::Bencher;
 
+#[test]
+fn kuhn_duplicate() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    perf::{duplicate_rates, run_benchmark_duplicate},
+    players::{PlayersFold, PlayersRand},
+    players_mul::{PlayersMul2, PlayersMulX},
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+  let funds = vec![(0, fund), (1, fund)];
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  // Identical deterministic players break even on every deal.
+  let ref mut players = PlayersMulX(vec![
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  ]);
+
+  let deals_rates = duplicate_rates(&mut rng, sim, eval, &funds, 100, players);
+  assert!(deals_rates.len() == 100);
+  assert!(deals_rates.iter().all(|rates| rates.iter().all(|&r| r == 0.)));
+
+  let ref mut players = PlayersMul2(
+    (PlayersRand::new(rng, action_kuhn, blind), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  );
+
+  let rates = run_benchmark_duplicate(sim, eval, &funds, 200, players);
+  assert!((rates[0] + rates[1]).abs() < 1e-3);
+}
+
+#[test]
+fn kuhn_aivat() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    aivat::{Aivat, ValueShowdown},
+    cfr::{Cfr, PlayersStrategy},
+    game::GameState,
+    perf::aivat_rates,
+    players::PlayersRand,
+    players_mul::PlayersMul2,
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+  let funds = vec![(0, fund), (1, fund)];
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let mut cfr = Cfr::new(sim, eval, &vec![fund; 2], false).unwrap();
+  cfr.run(200);
+  let strategy = cfr.strategy();
+
+  let ref mut players = PlayersMul2(
+    (PlayersStrategy::new(rng.clone(), action_kuhn, strategy.clone()), HashSet::from_iter(vec![0])),
+    (PlayersRand::new(rng.clone(), action_kuhn, blind), HashSet::from_iter(vec![1])),
+  );
+
+  let policy = |state: &GameState| {
+    strategy.get(&state.infoset_key(state.active)).cloned().unwrap_or(Vec::new())
+  };
+
+  let ref value = ValueShowdown { samples: 10 };
+  let aivat = Aivat::new(value, 10);
+
+  let hands = 4000;
+  let hands_rates = aivat_rates(&mut rng, sim, eval, &funds, hands, players, &aivat, 0, policy);
+
+  let raw: Vec<f32> = hands_rates.iter().map(|&(ref rates, _)| rates[0]).collect();
+  let corrected: Vec<f32> = hands_rates.iter().map(|&(_, r)| r).collect();
+
+  let mean = |xs: &Vec<f32>| xs.iter().sum::<f32>() / xs.len() as f32;
+  let variance = |xs: &Vec<f32>| {
+    let m = mean(xs);
+    xs.iter().map(|x| (x - m) * (x - m)).sum::<f32>() / xs.len() as f32
+  };
+
+  // Same expectation, with less variance.
+  assert!(variance(&corrected) < variance(&raw) / 2.);
+  assert!((mean(&raw) - mean(&corrected)).abs() < 3. * (variance(&raw) / hands as f32).sqrt());
+}
+
+#[test]
+fn kuhn_sprt() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    perf::{run_benchmark_sprt, run_benchmark_stats, Decision, RateStats, Sprt},
+    players::{PlayersFold, PlayersRand},
+    players_mul::{PlayersMul2, PlayersMulX},
+    OddChip, Sim,
+  };
+
+  let stats = RateStats::new(&vec![1., 2., 3., 4., 5.]);
+  assert!(stats.mean == 3. && stats.samples == 5);
+  assert!((stats.std - 2.5f32.sqrt()).abs() < 1e-6);
+  let (low, high) = stats.interval();
+  assert!(low < 3. && 3. < high && (high - low - 2. * 1.96 * stats.std_error()).abs() < 1e-5);
+  assert!(!stats.is_better(&RateStats::new(&vec![2., 3., 4.])));
+  assert!(stats.is_better(&RateStats::new(&vec![-10., -11., -12., -10., -11.])));
+
+  let rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+  let funds = vec![(0, fund), (1, fund)];
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let sprt = Sprt::new(100.);
+
+  // Indistinguishable players play up to the hands limit.
+  let ref mut players = PlayersMulX(vec![
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  ]);
+
+  let (stats, decision) = run_benchmark_sprt(sim, eval, &funds, 200, players, 0, &sprt);
+  assert!(decision == Decision::Undecided);
+  assert!(stats.samples == 100 && stats.mean == 0.);
+
+  // Folding everything loses to a random player, which is decided early.
+  let ref mut players = PlayersMul2(
+    (PlayersRand::new(rng, action_kuhn, blind), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  );
+
+  let (stats, decision) = run_benchmark_sprt(sim, eval, &funds, 100_000, players, 1, &sprt);
+  assert!(decision == Decision::Worse);
+  assert!(stats.samples < 1000);
+
+  let stats = run_benchmark_stats(sim, eval, &funds, 2000, players);
+  assert!(stats[0].samples == 1000);
+  assert!(stats[0].interval().0 > 0. && stats[1].interval().1 < 0.);
+}
+
+#[test]
+fn kuhn_breakdown() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{
+    cards::{Card, CardVal, Suit},
+    classifiers::ActionKuhn,
+    profile::profile_kuhn,
+    Money,
+  };
+  use anna_simulation::{
+    perf::{hand_class, run_benchmark_breakdown, Rate},
+    players::{PlayersFold, PlayersRand},
+    players_mul::PlayersMul2,
+    OddChip, Sim,
+  };
+
+  let rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+  let fund = Money::new(100, 0);
+  let funds = vec![(0, fund), (1, fund)];
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let ref mut players = PlayersMul2(
+    (PlayersRand::new(rng, action_kuhn, blind), HashSet::from_iter(vec![0])),
+    (PlayersFold::new(action_kuhn), HashSet::from_iter(vec![1])),
+  );
+
+  let breakdown = run_benchmark_breakdown(sim, eval, &funds, 1000, players, 0);
+  assert!(breakdown.rates.len() == 1000);
+
+  // The shares of every grouping sum to the overall rate.
+  let overall = breakdown.share(&breakdown.rates);
+  let sum =
+    |groups: Vec<&Vec<Rate>>| groups.iter().map(|rates| breakdown.share(rates)).sum::<f32>();
+  assert!((sum(breakdown.positions.values().collect()) - overall).abs() < 1e-2);
+  assert!((sum(breakdown.streets.values().collect()) - overall).abs() < 1e-2);
+  assert!((sum(vec![&breakdown.showdown, &breakdown.showdown_non]) - overall).abs() < 1e-2);
+  assert!((sum(breakdown.hand_classes.values().collect()) - overall).abs() < 1e-2);
+
+  assert!(breakdown.positions.keys().cloned().collect::<Vec<_>>() == vec![0, 1]);
+  assert!(breakdown.streets.keys().cloned().collect::<Vec<_>>() == vec![0]);
+  assert!(breakdown.hand_classes.keys().all(|class| ["J", "Q", "K"].contains(&class.as_str())));
+
+  // Bets of the random player are folded to, a red line winning.
+  assert!(!breakdown.showdown.is_empty() && breakdown.share(&breakdown.showdown_non) > 0.);
+
+  let card = |value, suit| Card { value: value, suit: suit };
+  let ace_spade = card(CardVal::CA, Suit::Spade);
+  assert!(hand_class(&[card(CardVal::CK, Suit::Spade), ace_spade]) == "AKs");
+  assert!(hand_class(&[ace_spade, card(CardVal::CK, Suit::Heart)]) == "AKo");
+  assert!(hand_class(&[card(CardVal::C7, Suit::Club), card(CardVal::C7, Suit::Heart)]) == "77");
+}
+
 #[bench]
 fn kuhn2_bench(bencher: &mut Bencher) {
   use env_logger;
@@ -25,7 +283,7 @@ fn kuhn2_bench(bencher: &mut Bencher) {
     players::{PlayersFold, PlayersRand},
     players_kuhn::Kuhn2,
     players_mul::PlayersMulX,
-    Sim,
+    OddChip, Sim,
   };
 
   let seed: &[_] = &[0];
@@ -44,7 +302,14 @@ fn kuhn2_bench(bencher: &mut Bencher) {
 
   let ref action_kuhn = ActionKuhn {};
 
-  let ref sim = Sim { action_class: action_kuhn, blind_biggest: blind, profile: profile_kuhn(2) };
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
 
   let ref mut players_fold = PlayersFold::new(action_kuhn);
 
@@ -0,0 +1,78 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_recorder_replay() {
+  use rand::{SeedableRng, StdRng};
+  use std::{env, fs};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    engine::table_game_simulate,
+    players::PlayersRand,
+    players_recorder::{records_load, records_replay, PlayersRecorder, Record},
+    OddChip, Sim,
+  };
+
+  let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+  let rng_players: StdRng = SeedableRng::from_seed([1; 32]);
+
+  let ref eval = Eval::naive();
+
+  let blind = Money::new(1, 0);
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let path = env::temp_dir().join("anna_players_recorder.jsonl");
+
+  {
+    let players = PlayersRand::new(rng_players.clone(), action_kuhn, blind);
+    let ref mut recorder = PlayersRecorder::create(players, &path).unwrap();
+
+    for i in 0..20 {
+      table_game_simulate(&mut rng, sim, eval, recorder, &funds, i % 2)
+        .map_err(|(_, e)| e)
+        .unwrap();
+    }
+    assert!(recorder.error.is_none());
+  }
+
+  let records = records_load(&path).unwrap();
+  let inits = records
+    .iter()
+    .filter(|r| match r {
+      &&Record::Init { .. } => true,
+      _ => false,
+    })
+    .count();
+  let ends = records
+    .iter()
+    .filter(|r| match r {
+      &&Record::GameEnd { .. } => true,
+      _ => false,
+    })
+    .count();
+  assert!(inits == 20 && ends == 20);
+
+  // The same players play the same actions.
+  let ref mut players = PlayersRand::new(rng_players, action_kuhn, blind);
+  let plays = records_replay(&records, players);
+  assert!(plays.len() >= 40);
+  assert!(plays.iter().all(|&(recorded, replayed)| recorded.is_some() && recorded == replayed));
+
+  let _ = fs::remove_file(&path);
+}
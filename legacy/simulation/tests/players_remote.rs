@@ -0,0 +1,201 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_remote_tcp() {
+  use rand::{SeedableRng, StdRng};
+  use std::collections::HashSet;
+  use std::net::TcpListener;
+  use std::thread;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    perf::run_benchmark,
+    players::{PlayersFold, PlayersRand},
+    players_mul::PlayersMul2,
+    players_remote::{client_serve, client_tcp, PlayersRemote},
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let blind = Money::new(1, 0);
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: blind,
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  let client = thread::spawn(move || {
+    let ref action_kuhn = ActionKuhn {};
+    let rng: StdRng = SeedableRng::from_seed([0; 32]);
+    let mut players = PlayersRand::new(rng, action_kuhn, blind);
+    client_serve(client_tcp(addr).unwrap(), &mut players)
+  });
+
+  let remote = PlayersRemote::tcp_accept(&listener, action_kuhn).unwrap();
+  let fold = PlayersFold::new(action_kuhn);
+
+  let seats = |s: usize| -> HashSet<usize> { vec![s].into_iter().collect() };
+  let mut players = PlayersMul2((remote, seats(0)), (fold, seats(1)));
+
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+  let rates = run_benchmark(sim, eval, &funds, 100, &mut players);
+  assert!(rates.len() == 2);
+  assert!(((players.0).0).error.is_none());
+
+  // Closing the connection ends the client.
+  drop(players);
+  assert!(client.join().unwrap().is_ok());
+}
+
+#[test]
+fn kuhn_remote_unix_illegal() {
+  use std::env;
+  use std::fs;
+  use std::io::{BufRead, BufReader, Write};
+  use std::os::unix::net::UnixListener;
+  use std::thread;
+
+  use anna_model::{classifiers::ActionKuhn, Money};
+  use anna_simulation::{
+    players::Players,
+    players_remote::{client_unix, PlayersRemote},
+  };
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let path = env::temp_dir().join("anna_players_remote.sock");
+  let _ = fs::remove_file(&path);
+  let listener = UnixListener::bind(&path).unwrap();
+
+  // A client answering every observation with an action out of the action class.
+  let client_path = path.clone();
+  let client = thread::spawn(move || {
+    let stream = client_unix(&client_path).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap() > 0 {
+      if line.contains("\"observation\"") {
+        writer.write_all(b"{\"action\":9}\n").unwrap();
+      }
+      lines.push(line.clone());
+      line.clear();
+    }
+    lines
+  });
+
+  let mut remote = PlayersRemote::unix_accept(&listener, action_kuhn).unwrap();
+
+  let blinds = vec![Money::new(1, 0); 2];
+  let funds = vec![Money::new(100, 0); 2];
+  remote.init(&blinds, 0, &funds, &vec![(0, vec![])]);
+  let play = remote.play(0, Money::new(1, 0), None, &blinds, 0, Money::new(99, 0), &[]);
+  assert!(play.is_err());
+  assert!(remote.error.is_some());
+
+  drop(remote);
+  let lines = client.join().unwrap();
+  assert!(lines.len() == 2);
+  assert!(lines[0].starts_with("{\"type\":\"init\""));
+  assert!(lines[1].contains("\"legal_actions\":["));
+
+  let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn kuhn_remote_deadline() {
+  use std::io::{BufRead, BufReader, Write};
+  use std::net::TcpListener;
+  use std::thread;
+  use std::time::{Duration, Instant};
+
+  use anna_model::{classifiers::ActionKuhn, Money};
+  use anna_simulation::{
+    players::Players,
+    players_remote::{client_tcp, PlayersRemote},
+  };
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  // A client calling, too late for its first observation and sending half of its reply in time
+  // for the third one.
+  let client = thread::spawn(move || {
+    let stream = client_tcp(addr).unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    let mut observations = 0;
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap() > 0 {
+      if line.contains("\"observation\"") {
+        match observations {
+          0 => {
+            thread::sleep(Duration::from_millis(200));
+            writer.write_all(b"{\"action\":1}\n").unwrap();
+          }
+          2 => {
+            writer.write_all(b"{\"act").unwrap();
+            thread::sleep(Duration::from_millis(200));
+            writer.write_all(b"ion\":1}\n").unwrap();
+          }
+          _ => writer.write_all(b"{\"action\":1}\n").unwrap(),
+        }
+        observations += 1;
+      }
+      line.clear();
+    }
+    observations
+  });
+
+  let mut remote = PlayersRemote::tcp_accept(&listener, action_kuhn).unwrap();
+
+  let blinds = vec![Money::new(1, 0); 2];
+  let funds = vec![Money::new(100, 0); 2];
+  let pots = vec![Money::new(1, 0), Money::new(2, 0)];
+  remote.init(&blinds, 0, &funds, &vec![(0, vec![])]);
+
+  // Given up at the deadline, folding as the engine would.
+  let start = Instant::now();
+  remote.deadline(0, Some(start + Duration::from_millis(20)));
+  let play = remote.play(0, Money::new(2, 0), None, &pots, 0, Money::new(99, 0), &[]);
+  assert!(play == Ok(0));
+  assert!(start.elapsed() < Duration::from_millis(150));
+
+  // The late reply is skipped.
+  remote.deadline(0, None);
+  let play = remote.play(0, Money::new(2, 0), None, &pots, 0, Money::new(99, 0), &[]);
+  assert!(play == Ok(1));
+  assert!(remote.error.is_none());
+
+  // The line cut at the deadline is completed by the next read, then skipped.
+  let start = Instant::now();
+  remote.deadline(0, Some(start + Duration::from_millis(20)));
+  let play = remote.play(0, Money::new(2, 0), None, &pots, 0, Money::new(99, 0), &[]);
+  assert!(play == Ok(0));
+
+  remote.deadline(0, None);
+  let play = remote.play(0, Money::new(2, 0), None, &pots, 0, Money::new(99, 0), &[]);
+  assert!(play == Ok(1));
+  assert!(remote.error.is_none());
+
+  drop(remote);
+  assert!(client.join().unwrap() == 4);
+}
@@ -0,0 +1,102 @@
+extern crate rand;
+
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn rules_chen() {
+  use anna_model::cards::Card;
+  use anna_simulation::{acpc::card_parse, players_rules::chen};
+
+  let hand =
+    |s: &str| -> Vec<Card> { vec![card_parse(&s[0..2]).unwrap(), card_parse(&s[2..4]).unwrap()] };
+
+  assert!(chen(&hand("AsAh")) == 20.);
+  assert!(chen(&hand("AsKs")) == 12.);
+  assert!(chen(&hand("JsTs")) == 9.);
+  assert!(chen(&hand("2s2h")) == 5.);
+  assert!(chen(&hand("7s2h")) == -1.);
+}
+
+#[test]
+fn rules_texas_limit() {
+  use rand::{SeedableRng, StdRng};
+  use std::{collections::HashSet, iter::FromIterator};
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_texas_limit, Money};
+  use anna_simulation::{
+    perf::run_benchmark_stats,
+    players::PlayersRand,
+    players_mul::PlayersMul2,
+    players_rules::{PlayersRules, Style, STYLES},
+    OddChip, Sim,
+  };
+
+  let rng: StdRng = SeedableRng::from_seed([0; 32]);
+
+  let ref eval = Eval::texas();
+
+  let blind_big = Money::new(1, 0);
+  let ref profile = profile_texas_limit(2, blind_big / 2, blind_big);
+
+  let ref action_class = ActionLimit { raises: profile.limit.clone().unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_class,
+    blind_biggest: blind_big,
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let funds = vec![(0, Money::new(100, 0)), (1, Money::new(100, 0))];
+
+  // Every preset plays legal actions against every other one.
+  for a in STYLES.iter() {
+    for b in STYLES.iter() {
+      let ref mut players = PlayersMul2(
+        (
+          PlayersRules::new(
+            rng.clone(),
+            16,
+            action_class,
+            eval,
+            profile,
+            Style::preset(a).unwrap(),
+          ),
+          HashSet::from_iter(vec![0]),
+        ),
+        (
+          PlayersRules::new(
+            rng.clone(),
+            16,
+            action_class,
+            eval,
+            profile,
+            Style::preset(b).unwrap(),
+          ),
+          HashSet::from_iter(vec![1]),
+        ),
+      );
+      run_benchmark_stats(sim, eval, &funds, 20, players);
+    }
+  }
+
+  assert!(Style::preset("fish").is_none());
+  assert!(Style::maniac().probs(0.)[2] > Style::nit().probs(0.)[2]);
+  assert!(Style::calling_station().probs(0.)[0] < Style::nit().probs(0.)[0]);
+
+  // A tight aggressive player beats random actions.
+  let ref mut players = PlayersMul2(
+    (
+      PlayersRules::new(rng.clone(), 64, action_class, eval, profile, Style::tag()),
+      HashSet::from_iter(vec![0]),
+    ),
+    (PlayersRand::new(rng.clone(), action_class, blind_big), HashSet::from_iter(vec![1])),
+  );
+  let stats = run_benchmark_stats(sim, eval, &funds, 1000, players);
+  assert!(stats[0].interval().0 > 0.);
+}
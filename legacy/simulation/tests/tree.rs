@@ -0,0 +1,95 @@
+extern crate anna_eval;
+extern crate anna_model;
+extern crate anna_simulation;
+
+#[test]
+fn kuhn_tree() {
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionKuhn, profile::profile_kuhn, Money};
+  use anna_simulation::{
+    tree::{Node, Tree},
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::naive();
+
+  let ref action_kuhn = ActionKuhn {};
+
+  let ref sim = Sim {
+    action_class: action_kuhn,
+    blind_biggest: Money::new(1, 0),
+    profile: profile_kuhn(2),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let funds = vec![Money::new(100, 0); 2];
+  let tree = Tree::build(sim, eval, &funds, 0).unwrap();
+
+  // 6 deals, each with 4 decision and 5 terminal nodes.
+  assert!(tree.nodes.len() == 1 + 6 * 9);
+  assert!(tree.infosets().len() == 12);
+  for (_, node_ids) in tree.infosets() {
+    assert!(node_ids.len() == 2);
+  }
+
+  let mut visited = 0;
+  tree.walk(|_, node, _| {
+    visited += 1;
+    if let &Node::Terminal { ref payoffs } = node {
+      assert!(payoffs.iter().map(|&(_, m)| m).sum::<i32>() == 0);
+    }
+  });
+  assert!(visited == tree.nodes.len());
+}
+
+#[test]
+fn leduc_tree() {
+  use std::env;
+
+  use anna_eval::Eval;
+  use anna_model::{classifiers::ActionLimit, profile::profile_leduc, Money};
+  use anna_simulation::{
+    tree::{Node, Tree},
+    OddChip, Sim,
+  };
+
+  let ref eval = Eval::naive();
+
+  let ref profile = profile_leduc(2);
+
+  let ref action_leduc = ActionLimit { raises: profile.clone().limit.unwrap().raises };
+
+  let ref sim = Sim {
+    action_class: action_leduc,
+    blind_biggest: Money::new(1, 0),
+    profile: profile.clone(),
+    strict: true,
+    odd_chip: OddChip::ButtonLeft,
+    budget: None,
+  };
+
+  let funds = vec![Money::new(100, 0); 2];
+  let tree = Tree::build(sim, eval, &funds, 0).unwrap();
+
+  // Private hands and table cards are dealt by chance nodes.
+  let mut chances = 0;
+  let mut depth_max = 0;
+  tree.walk(|_, node, depth| {
+    depth_max = depth_max.max(depth);
+    if let &Node::Chance { ref outcomes } = node {
+      chances += 1;
+      let p: f32 = outcomes.iter().map(|&(_, p, _)| p).sum();
+      assert!((p - 1.).abs() < 1e-4);
+    }
+  });
+  assert!(chances > 1);
+  assert!(depth_max > 2);
+
+  let path = env::temp_dir().join("anna_leduc_tree.bin");
+  tree.save(&path).unwrap();
+  let tree_loaded = Tree::load(&path).unwrap();
+  assert!(tree_loaded.nodes.len() == tree.nodes.len());
+  assert!(tree_loaded.infosets() == tree.infosets());
+}
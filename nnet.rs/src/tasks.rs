@@ -93,6 +93,18 @@ where
     target: &Tensor<O>,
     feeds: &[(String, f32)],
     fetches: &[String],
+  ) -> Result<Vec<f32>, Error> {
+    self.run_with(input, target, feeds, &[], fetches)
+  }
+
+  // Same as `run_`, feeding tensors along with the scalars (e.g. the weights of the samples).
+  pub fn run_with(
+    &mut self,
+    input: &Tensor<I>,
+    target: &Tensor<O>,
+    feeds: &[(String, f32)],
+    feeds_tensors: &[(String, &Tensor<f32>)],
+    fetches: &[String],
   ) -> Result<Vec<f32>, Error> {
     use crate::tensor_of;
 
@@ -105,6 +117,11 @@ where
       feeds_tops.push((t, op))
     }
 
+    let mut feeds_tensors_ops = Vec::new();
+    for (feed, _) in feeds_tensors.iter() {
+      feeds_tensors_ops.push(op.graph.operation_by_name_required(&feed)?);
+    }
+
     let mut fetches_ops = Vec::new();
     for fetch in fetches {
       fetches_ops.push(op.graph.operation_by_name_required(fetch)?);
@@ -120,6 +137,9 @@ where
     for i in 0..feeds_tops.len() {
       ctx.add_feed(&feeds_tops[i].1, 0, &feeds_tops[i].0);
     }
+    for i in 0..feeds_tensors_ops.len() {
+      ctx.add_feed(&feeds_tensors_ops[i], 0, feeds_tensors[i].1);
+    }
 
     let mut fetches_tokens = Vec::new();
     for op in fetches_ops {